@@ -41,13 +41,15 @@ impl BuildCommand {
         }
 
         let start = Instant::now();
-        let result = cmd.site.build();
+        let warnings = cmd.site.build()?;
         let duration = start.elapsed();
 
-        if result.is_ok() {
-            bunt::writeln!(stdout, "Site built in {$bold}{:?}{/$}\n", duration)?;
+        for warning in warnings {
+            bunt::writeln!(stdout, "{$bold}{$yellow}Warning:{/$}{/$} {}\n", warning)?;
         }
 
-        result
+        bunt::writeln!(stdout, "Site built in {$bold}{:?}{/$}\n", duration)?;
+
+        Ok(())
     }
 }
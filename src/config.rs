@@ -1,13 +1,17 @@
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fmt;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 use colorsys::prelude::*;
 use colorsys::Rgb;
-use serde::Deserialize;
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
 
-use crate::{Error, Result};
+use crate::navigation::{GroupOrder, Link, SortMode};
 use crate::site::BuildMode;
-use crate::navigation::Link;
+use crate::{Error, Result};
 
 #[derive(Debug, Clone, Deserialize)]
 struct DoctaveYaml {
@@ -15,10 +19,36 @@ struct DoctaveYaml {
     port: Option<u32>,
     colors: Option<ColorsYaml>,
     logo: Option<PathBuf>,
-    navigation: Option<Vec<Navigation>>,
+    navigation: Option<NavigationYaml>,
+    navigation_file: Option<PathBuf>,
+    clean_urls: Option<bool>,
+    trailing_slash: Option<bool>,
+    base_path: Option<String>,
+    slugify_titles: Option<bool>,
+    nav_depth: Option<usize>,
+    nav_expand_depth: Option<usize>,
+    nav_exclude: Option<Vec<String>>,
+    index_filenames: Option<Vec<String>>,
+    nav_title_max_len: Option<usize>,
+    sort_direction: Option<String>,
+    group_order: Option<String>,
+    group_by: Option<String>,
+    title_case: Option<bool>,
+    collapse_single: Option<bool>,
+    index_as_child: Option<bool>,
+    index_child_title: Option<String>,
+    show_home: Option<bool>,
+    home_title: Option<String>,
+    docs_dir: Option<String>,
+    extra_docs_dirs: Option<Vec<String>>,
 }
 
 impl DoctaveYaml {
+    /// The name of the directory in the project root holding the docs
+    fn docs_dir_name(&self) -> &str {
+        self.docs_dir.as_deref().unwrap_or("docs")
+    }
+
     fn find(root: &Path) -> Option<PathBuf> {
         if root.join("doctave.yaml").exists() {
             Some(root.join("doctave.yaml"))
@@ -29,6 +59,85 @@ impl DoctaveYaml {
         }
     }
 
+    /// Reads the navigation from the file set in `navigation_file`, if any.
+    /// The path is relative to the project root.
+    fn load_navigation_file(&mut self, project_root: &Path) -> Result<()> {
+        if let Some(file) = &self.navigation_file {
+            if self.navigation.is_some() {
+                return Err(Error::new(
+                    "Found both navigation and navigation_file in doctave.yaml. \
+                     Please only use one of them.",
+                ));
+            }
+
+            let location = project_root.join(file);
+            let yaml = fs::read_to_string(&location).map_err(|e| {
+                Error::io(
+                    e,
+                    format!(
+                        "Could not read navigation file specified in doctave.yaml at {}",
+                        location.display()
+                    ),
+                )
+            })?;
+
+            let navigation = serde_yaml::from_str(&yaml).map_err(|e| {
+                Error::yaml(
+                    e,
+                    format!("Could not parse navigation file {}", location.display()),
+                )
+            })?;
+
+            self.navigation = Some(navigation);
+        }
+
+        Ok(())
+    }
+
+    /// Replaces `${VAR}` in the URLs and titles of external links with the
+    /// value of the environment variable. Paths to local files are left
+    /// untouched.
+    fn substitute_env_vars(&mut self) -> Result<()> {
+        fn substitute_level(navs: &mut [Navigation]) -> Result<()> {
+            for nav in navs {
+                if let Some(url) = &nav.url {
+                    let lookup = |name: &str| std::env::var(name).ok();
+
+                    nav.url = Some(substitute_vars(url, lookup)?);
+                    nav.title = nav
+                        .title
+                        .as_ref()
+                        .map(|title| substitute_vars(title, lookup))
+                        .transpose()?;
+                }
+
+                if let Some(NavChildren::List(children)) = &mut nav.children {
+                    substitute_level(children)?;
+                }
+            }
+
+            Ok(())
+        }
+
+        match &mut self.navigation {
+            None => Ok(()),
+            Some(NavigationYaml::List(navs)) => substitute_level(navs),
+            Some(NavigationYaml::Named(menus)) => menus
+                .iter_mut()
+                .try_for_each(|(_, navs)| substitute_level(navs)),
+        }
+    }
+
+    /// All the navigation menus, whether there is a single one or several
+    /// named ones
+    fn navigation_menus(&self) -> Vec<&[Navigation]> {
+        match &self.navigation {
+            None => vec![],
+            Some(NavigationYaml::List(navs)) => vec![navs],
+            Some(NavigationYaml::Named(menus)) => menus.iter().map(|(_, m)| &m[..]).collect(),
+        }
+    }
+
     /// Runs checks that validate the values of provided in the Yaml file
     fn validate(&self, project_root: &Path) -> Result<()> {
         // Validate color
@@ -42,9 +151,45 @@ impl DoctaveYaml {
             })?;
         }
 
+        // Validate docs directory name
+        if let Some(name) = &self.docs_dir {
+            if name.is_empty() || name.contains(|c| c == '/' || c == '\\') {
+                return Err(Error::new(format!(
+                    "Invalid docs_dir in doctave.yaml. \
+                     Found '{}', expected the name of a directory in the project root",
+                    name
+                )));
+            }
+        }
+
+        // Validate the other directories with pages exist
+        for name in self.extra_docs_dirs.iter().flatten() {
+            if name.is_empty()
+                || name.contains(|c| c == '/' || c == '\\')
+                || name == self.docs_dir_name()
+            {
+                return Err(Error::new(format!(
+                    "Invalid extra_docs_dirs in doctave.yaml. \
+                     Found '{}', expected the name of a directory in the project root \
+                     other than the docs directory",
+                    name
+                )));
+            }
+
+            if !project_root.join(name).is_dir() {
+                return Err(Error::new(format!(
+                    "Could not find the directory {} listed in extra_docs_dirs in doctave.yaml",
+                    project_root.join(name).display()
+                )));
+            }
+        }
+
         // Validate logo exists
         if let Some(p) = &self.logo {
-            let location = project_root.join("docs").join("_include").join(p);
+            let location = project_root
+                .join(self.docs_dir_name())
+                .join("_include")
+                .join(p);
             if !location.exists() {
                 return Err(Error::new(format!(
                     "Could not find logo specified in doctave.yaml at {}.\n\
@@ -54,6 +199,58 @@ impl DoctaveYaml {
             }
         }
 
+        // Validate sort direction
+        if let Some(direction) = &self.sort_direction {
+            if direction != "asc" && direction != "desc" {
+                return Err(Error::new(format!(
+                    "Invalid sort_direction in doctave.yaml. \
+                     Found '{}', expected \"asc\" or \"desc\"",
+                    direction
+                )));
+            }
+        }
+
+        // Validate the order of pages and directories
+        if let Some(group_order) = &self.group_order {
+            if GroupOrder::from_name(group_order).is_none() {
+                return Err(Error::new(format!(
+                    "Invalid group_order in doctave.yaml. \
+                     Found '{}', expected \"files_first\", \"dirs_first\" or \"mixed\"",
+                    group_order
+                )));
+            }
+        }
+
+        // Validate index file names
+        if let Some(names) = &self.index_filenames {
+            if names.is_empty() {
+                return Err(Error::new(
+                    "Invalid index_filenames in doctave.yaml. Expected at least one file name",
+                ));
+            }
+
+            for name in names {
+                if name.contains('/') || !name.ends_with(".md") {
+                    return Err(Error::new(format!(
+                        "Invalid index_filenames in doctave.yaml. \
+                         Found '{}', expected the name of a Markdown file like \"README.md\"",
+                        name
+                    )));
+                }
+            }
+        }
+
+        // Validate grouping
+        if let Some(group_by) = &self.group_by {
+            if group_by != "category" {
+                return Err(Error::new(format!(
+                    "Invalid group_by in doctave.yaml. \
+                     Found '{}', expected \"category\"",
+                    group_by
+                )));
+            }
+        }
+
         // Validate navigation paths exist
         // Validate navigation wildcards recursively
         fn validate_level(
@@ -61,20 +258,74 @@ impl DoctaveYaml {
             config: &DoctaveYaml,
             project_root: &Path,
         ) -> Result<()> {
-            if !project_root.join(&nav.path).exists() {
+            if NavRule::is_divider(nav) || NavRule::is_auto(nav) {
+                return Ok(());
+            }
+
+            if let Some(url) = &nav.url {
+                if nav.title.is_none() {
+                    return Err(Error::new(format!(
+                        "Missing title for external link {} in navigation",
+                        url
+                    )));
+                }
+
+                return Ok(());
+            }
+
+            if NavRule::is_generated(nav) && nav.title.is_none() {
+                return Err(Error::new(format!(
+                    "Missing title for generated section {} in navigation",
+                    nav.path.display()
+                )));
+            }
+
+            let is_group = NavRule::is_group(nav);
+
+            if nav.path.as_os_str().is_empty() && !is_group {
+                return Err(Error::new(
+                    "Found a navigation item without a path. \
+                     Expected either a path, a url, or a title with a list of children.",
+                ));
+            }
+
+            let docs_dir = config.docs_dir_name();
+            if !is_group && !NavRule::in_docs_dir(&nav.path, docs_dir) {
+                return Err(Error::new(format!(
+                    "Found {} in navigation, which is not inside the docs directory. \
+                     Paths in the navigation start with the docs directory, like {}/{}",
+                    nav.path.display(),
+                    docs_dir,
+                    nav.path.display()
+                )));
+            }
+
+            if !is_group && !NavRule::is_glob(&nav.path) && !project_root.join(&nav.path).exists() {
                 return Err(Error::new(format!(
                     "Could not find file specified in navigation at {}",
                     nav.path.display()
                 )));
             }
 
+            if let Some(sort) = &nav.sort {
+                if SortMode::from_name(sort).is_none() {
+                    return Err(Error::new(format!(
+                        "Invalid sort for {} in navigation. \
+                         Found '{}', expected \"title\", \"order\", \"reverse\", \"date\", \
+                         \"semver\" or \"none\"",
+                        nav.path.display(),
+                        sort
+                    )));
+                }
+            }
+
             if let Some(children) = &nav.children {
                 match children {
                     NavChildren::WildCard(pattern) => {
-                        if pattern != "*" {
+                        if pattern != "*" && pattern != "**" {
                             return Err(Error::new(format!(
                                 "Invalid pattern for navigation children. \
-                                 Found '{}', expected \"*\" or a list of child pages",
+                                 Found '{}', expected \"*\", \"**\" or a list of child pages",
                                 pattern
                             )));
                         }
@@ -84,13 +335,33 @@ impl DoctaveYaml {
                             validate_level(&nav, config, project_root)?;
                         }
                     }
+                    NavChildren::Except(patterns) => {
+                        for (pattern, except) in patterns {
+                            if pattern != "*" {
+                                return Err(Error::new(format!(
+                                    "Invalid pattern for navigation children. \
+                                     Found '{}', expected \"*\" before an except list",
+                                    pattern
+                                )));
+                            }
+
+                            for path in &except.except {
+                                if !project_root.join(&nav.path).join(path).exists() {
+                                    return Err(Error::new(format!(
+                                        "Could not find file excluded in navigation at {}",
+                                        nav.path.join(path).display()
+                                    )));
+                                }
+                            }
+                        }
+                    }
                 }
             }
 
             Ok(())
         }
 
-        if let Some(navs) = &self.navigation {
+        for navs in self.navigation_menus() {
             for nav in navs {
                 validate_level(nav, &self, &project_root)?;
             }
@@ -99,9 +370,47 @@ impl DoctaveYaml {
         Ok(())
     }
 }
+/// The navigation can either be a single list, or a map of named lists
+/// when the site has more than one menu. The menus are kept in the order
+/// they are written in.
 #[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum NavigationYaml {
+    #[serde(deserialize_with = "navigation_list")]
+    List(Vec<Navigation>),
+    #[serde(deserialize_with = "named_navigation_lists")]
+    Named(Vec<(String, Vec<Navigation>)>),
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
 pub struct Navigation {
+    #[serde(default)]
     pub path: PathBuf,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    /// Where an external link opens, like `_blank` or `_self`
+    pub target: Option<String>,
+    pub icon: Option<String>,
+    pub description: Option<String>,
+    pub badges: Option<Vec<String>>,
+    pub anchor: Option<String>,
+    /// CSS classes for the link, separated by spaces
+    pub class: Option<String>,
+    pub collapsed: Option<bool>,
+    pub include_overview: Option<String>,
+    pub link_to_first: Option<bool>,
+    /// Set to `false` to show a directory as a heading that doesn't link to
+    /// its index page
+    pub link: Option<bool>,
+    pub sort: Option<String>,
+    pub divider: Option<bool>,
+    pub auto: Option<bool>,
+    /// Lists every page under the directory in `path` as a flat section,
+    /// without needing an index page
+    pub generated: Option<bool>,
+    /// The build profiles the item is shown in. Shown in all of them if
+    /// not set.
+    pub only: Option<Vec<String>>,
     pub children: Option<NavChildren>,
 }
 
@@ -109,7 +418,86 @@ pub struct Navigation {
 #[serde(untagged)]
 pub enum NavChildren {
     WildCard(String),
+    #[serde(deserialize_with = "navigation_list")]
     List(Vec<Navigation>),
+    /// A wildcard that leaves out some of the pages, like
+    /// `{ "*": { except: [draft.md] } }`
+    Except(BTreeMap<String, NavExcept>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NavExcept {
+    /// Paths relative to the directory that should be left out
+    pub except: Vec<PathBuf>,
+}
+
+/// An item of a navigation list, which can be written as just its path
+/// when it needs no other options, like `- docs/install.md`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum NavigationItem {
+    Path(PathBuf),
+    Item(Navigation),
+}
+
+impl From<NavigationItem> for Navigation {
+    fn from(other: NavigationItem) -> Self {
+        match other {
+            NavigationItem::Path(path) => Navigation {
+                path,
+                ..Navigation::default()
+            },
+            NavigationItem::Item(nav) => nav,
+        }
+    }
+}
+
+fn navigation_list<'de, D>(deserializer: D) -> std::result::Result<Vec<Navigation>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let items = Vec::<NavigationItem>::deserialize(deserializer)?;
+
+    Ok(items.into_iter().map(Navigation::from).collect())
+}
+
+fn named_navigation_lists<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<(String, Vec<Navigation>)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct MenusVisitor;
+
+    impl<'de> Visitor<'de> for MenusVisitor {
+        type Value = Vec<(String, Vec<Navigation>)>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map of navigation menus")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut menus: Self::Value = vec![];
+
+            while let Some((name, items)) = map.next_entry::<String, Vec<NavigationItem>>()? {
+                if menus.iter().any(|(menu, _)| *menu == name) {
+                    return Err(de::Error::custom(format!(
+                        "the navigation menu {} is listed more than once",
+                        name
+                    )));
+                }
+
+                menus.push((name, items.into_iter().map(Navigation::from).collect()));
+            }
+
+            Ok(menus)
+        }
+    }
+
+    deserializer.deserialize_map(MenusVisitor)
 }
 
 static DEFAULT_THEME_COLOR: &str = "#445282";
@@ -142,55 +530,270 @@ impl Default for Colors {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum NavRule {
-    File(PathBuf),
-    Dir(PathBuf, Option<DirIncludeRule>),
+    /// A single page. The options can override how the page is shown in the
+    /// navigation.
+    File(PathBuf, LinkOptions),
+    Dir(PathBuf, Option<DirIncludeRule>, DirOptions),
+    /// A pattern like `docs/api/*.md` that expands to all the matching pages,
+    /// sorted alphanumerically. A `*` only matches within a single directory,
+    /// while `**` matches any number of nested directories.
+    Glob(PathBuf),
+    /// A link to an external URL, which doesn't need to match a document.
+    /// The target decides where the link opens, like `_blank` or `_self`.
+    Link {
+        title: String,
+        url: String,
+        target: Option<String>,
+        classes: Vec<String>,
+    },
+    /// A heading that groups the rules under it. Groups don't point to a
+    /// page themselves.
+    Group {
+        title: String,
+        children: Vec<NavRule>,
+    },
+    /// A line separating the links before and after it
+    Divider,
+    /// All the generated links of the level that the other rules don't
+    /// include, in their usual order
+    Auto,
+    /// A section with every page nested under the directory as its
+    /// children, sorted by title. Unlike `Dir`, the directory doesn't need
+    /// an index page, and the section doesn't link to one.
+    Generated {
+        title: String,
+        from: PathBuf,
+    },
+    /// A rule that only applies when building one of the listed profiles
+    Only {
+        profiles: Vec<String>,
+        rule: Box<NavRule>,
+    },
+}
+
+/// Options that change how a single link is presented in the navigation
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LinkOptions {
+    /// Overrides the title the page declares in its frontmatter
+    pub title: Option<String>,
+    /// Overrides the icon the page declares in its frontmatter
+    pub icon: Option<String>,
+    /// Overrides the description the page declares in its frontmatter
+    pub description: Option<String>,
+    /// Overrides the badges the page declares in its frontmatter
+    pub badges: Option<Vec<String>>,
+    /// Links to a heading inside the page instead of the page itself
+    pub anchor: Option<String>,
+    /// CSS classes added to the link
+    pub classes: Vec<String>,
+}
+
+/// Options that change how a directory is presented in the navigation
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DirOptions {
+    /// Render the directory collapsed, hiding its children until opened
+    pub collapsed: bool,
+    /// Lists the index of the directory as its first child, with this title
+    pub overview: Option<String>,
+    /// For a directory without an index page, links to its first child
+    /// instead of showing it as a group heading
+    pub link_to_first: bool,
+    /// Shows the directory as a heading that doesn't link anywhere, even if
+    /// it has an index page. Its children are kept.
+    pub unlinked: bool,
+    /// Sorts the children included with a wildcard differently from the
+    /// rest of the navigation
+    pub sort: Option<SortMode>,
+    /// Options for the link to the directory itself
+    pub link: LinkOptions,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum DirIncludeRule {
     WildCard,
+    /// Includes every page nested anywhere under the directory as a flat
+    /// list, leaving out the nested directories themselves.
+    DeepWildCard,
+    /// Includes every child except the listed paths
+    Except(Vec<PathBuf>),
     Explicit(Vec<NavRule>),
 }
 
 impl NavRule {
     fn from_yaml_input(input: Vec<Navigation>) -> Vec<NavRule> {
-        let mut rules = vec![];
-
-        for item in input {
-            if item.path.is_file() {
-                rules.push(NavRule::File(item.path.clone()));
-            } else if item.path.is_dir() {
-                let dir_rules = Self::build_directory_rules(&item);
-                rules.push(dir_rules);
-            }
+        input
+            .iter()
+            .map(Self::from_navigation)
+            // Top level paths that are neither a file nor a directory are
+            // left out
+            .filter(|rule| !Self::is_missing_dir(rule))
+            .collect()
+    }
+
+    /// Converts a single item of the navigation in doctave.yaml into a rule
+    fn from_navigation(item: &Navigation) -> NavRule {
+        let rule = if Self::is_divider(item) {
+            NavRule::Divider
+        } else if Self::is_auto(item) {
+            NavRule::Auto
+        } else if let Some(generated) = Self::generated(item) {
+            generated
+        } else if let Some(link) = Self::external_link(item) {
+            link
+        } else if let Some(group) = Self::group(item) {
+            group
+        } else if Self::is_glob(&item.path) {
+            NavRule::Glob(item.path.clone())
+        } else if item.path.is_file() {
+            NavRule::File(item.path.clone(), Self::link_options(item))
+        } else {
+            Self::build_directory_rules(item)
+        };
+
+        Self::only_in_profiles(item, rule)
+    }
+
+    fn is_missing_dir(rule: &NavRule) -> bool {
+        match rule {
+            NavRule::Only { rule, .. } => Self::is_missing_dir(rule),
+            NavRule::Dir(path, _, _) => !path.is_dir(),
+            _ => false,
         }
+    }
 
-        rules
+    /// Limits the rule to the profiles listed in the item, if any
+    fn only_in_profiles(item: &Navigation, rule: NavRule) -> NavRule {
+        match &item.only {
+            Some(profiles) => NavRule::Only {
+                profiles: profiles.clone(),
+                rule: Box::new(rule),
+            },
+            None => rule,
+        }
     }
 
     fn build_directory_rules(dir: &Navigation) -> NavRule {
+        let options = DirOptions {
+            collapsed: dir.collapsed.unwrap_or(false),
+            overview: dir.include_overview.clone(),
+            link_to_first: dir.link_to_first.unwrap_or(false),
+            unlinked: dir.link == Some(false),
+            sort: dir.sort.as_deref().and_then(SortMode::from_name),
+            link: Self::link_options(dir),
+        };
+
         match &dir.children {
-            None => NavRule::Dir(dir.path.clone(), None),
+            None => NavRule::Dir(dir.path.clone(), None, options),
+            Some(NavChildren::WildCard(pattern)) if pattern == "**" => NavRule::Dir(
+                dir.path.clone(),
+                Some(DirIncludeRule::DeepWildCard),
+                options,
+            ),
             Some(NavChildren::WildCard(_)) => {
-                NavRule::Dir(dir.path.clone(), Some(DirIncludeRule::WildCard))
+                NavRule::Dir(dir.path.clone(), Some(DirIncludeRule::WildCard), options)
+            }
+            Some(NavChildren::Except(patterns)) => {
+                let except = patterns
+                    .values()
+                    .flat_map(|e| e.except.iter().map(|p| dir.path.join(p)))
+                    .collect();
+
+                NavRule::Dir(
+                    dir.path.clone(),
+                    Some(DirIncludeRule::Except(except)),
+                    options,
+                )
             }
             Some(NavChildren::List(paths)) => NavRule::Dir(
                 dir.path.clone(),
                 Some(DirIncludeRule::Explicit(
-                    paths
-                        .iter()
-                        .map(|p| {
-                            if p.path.is_file() {
-                                NavRule::File(p.path.clone())
-                            } else {
-                                Self::build_directory_rules(p)
-                            }
-                        })
-                        .collect::<Vec<_>>(),
+                    paths.iter().map(Self::from_navigation).collect(),
                 )),
+                options,
             ),
         }
     }
+
+    fn link_options(item: &Navigation) -> LinkOptions {
+        LinkOptions {
+            title: item.title.clone(),
+            icon: item.icon.clone(),
+            description: item.description.clone(),
+            badges: item.badges.clone(),
+            anchor: item.anchor.clone(),
+            classes: Self::classes(item),
+        }
+    }
+
+    fn classes(item: &Navigation) -> Vec<String> {
+        item.class
+            .as_deref()
+            .map(|class| class.split_whitespace().map(|c| c.to_owned()).collect())
+            .unwrap_or_default()
+    }
+
+    fn external_link(item: &Navigation) -> Option<NavRule> {
+        item.url.as_ref().map(|url| NavRule::Link {
+            title: item.title.clone().unwrap_or_else(|| url.clone()),
+            url: url.clone(),
+            target: item.target.clone(),
+            classes: Self::classes(item),
+        })
+    }
+
+    fn group(item: &Navigation) -> Option<NavRule> {
+        match (&item.title, &item.children) {
+            (Some(title), Some(NavChildren::List(children))) if Self::is_group(item) => {
+                Some(NavRule::Group {
+                    title: title.clone(),
+                    children: Self::from_yaml_input(children.clone()),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Items with a title and a list of children, but no path or url, are
+    /// treated as groups.
+    fn is_group(item: &Navigation) -> bool {
+        item.path.as_os_str().is_empty()
+            && item.url.is_none()
+            && item.title.is_some()
+            && matches!(item.children, Some(NavChildren::List(_)))
+    }
+
+    fn is_divider(item: &Navigation) -> bool {
+        item.divider == Some(true)
+    }
+
+    /// Whether the path starts with the docs directory. The rest of the
+    /// path is the location of the page on the site.
+    fn in_docs_dir(path: &Path, docs_dir: &str) -> bool {
+        path.components().next() == Some(Component::Normal(OsStr::new(docs_dir)))
+    }
+
+    fn is_auto(item: &Navigation) -> bool {
+        item.auto == Some(true)
+    }
+
+    fn is_generated(item: &Navigation) -> bool {
+        item.generated == Some(true)
+    }
+
+    fn generated(item: &Navigation) -> Option<NavRule> {
+        if !Self::is_generated(item) {
+            return None;
+        }
+
+        Some(NavRule::Generated {
+            title: item.title.clone().unwrap_or_default(),
+            from: item.path.clone(),
+        })
+    }
+
+    fn is_glob(path: &Path) -> bool {
+        path.to_string_lossy().contains('*')
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -199,12 +802,38 @@ pub struct Config {
     project_root: PathBuf,
     out_dir: PathBuf,
     docs_dir: PathBuf,
+    docs_dir_name: String,
+    extra_docs_dirs: Vec<PathBuf>,
     title: String,
     colors: Colors,
     logo: Option<String>,
     navigation: Option<Vec<NavRule>>,
+    named_navigation: Vec<(String, Vec<NavRule>)>,
+    clean_urls: bool,
+    trailing_slash: bool,
+    base_path: String,
+    slugify_titles: bool,
+    nav_depth: Option<usize>,
+    nav_expand_depth: Option<usize>,
+    nav_exclude: Vec<String>,
+    index_filenames: Vec<String>,
+    nav_title_max_len: Option<usize>,
+    sort_descending: bool,
+    group_order: GroupOrder,
+    group_by_category: bool,
+    title_case: bool,
+    collapse_single: bool,
+    index_as_child: bool,
+    index_child_title: String,
+    show_home: bool,
+    home_title: String,
     port: u32,
     build_mode: BuildMode,
+    nav_json: bool,
+    summary: bool,
+    single_page: bool,
+    profile: Option<String>,
+    language: Option<String>,
 }
 
 impl Config {
@@ -219,25 +848,91 @@ impl Config {
     }
 
     pub fn from_yaml_str(project_root: &Path, yaml: &str) -> Result<Self> {
-        let doctave_yaml: DoctaveYaml = serde_yaml::from_str(yaml)
+        let mut doctave_yaml: DoctaveYaml = serde_yaml::from_str(yaml)
             .map_err(|e| Error::yaml(e, "Could not parse doctave.yaml"))?;
 
+        doctave_yaml.load_navigation_file(project_root)?;
+        doctave_yaml.substitute_env_vars()?;
         doctave_yaml.validate(project_root)?;
 
+        let docs_dir_name = doctave_yaml.docs_dir_name().to_owned();
+        let base_path = doctave_yaml
+            .base_path
+            .as_deref()
+            .map(|p| p.trim_matches('/'))
+            .filter(|p| !p.is_empty())
+            .map(|p| format!("/{}", p))
+            .unwrap_or_default();
+
+        let (navigation, named_navigation) = match doctave_yaml.navigation {
+            None => (None, vec![]),
+            Some(NavigationYaml::List(navs)) => (Some(NavRule::from_yaml_input(navs)), vec![]),
+            Some(NavigationYaml::Named(menus)) => (
+                None,
+                menus
+                    .into_iter()
+                    .map(|(name, navs)| (name, NavRule::from_yaml_input(navs)))
+                    .collect(),
+            ),
+        };
+
         let config = Config {
             color: true,
             project_root: project_root.to_path_buf(),
             out_dir: project_root.join("site"),
-            docs_dir: project_root.join("docs"),
+            docs_dir: project_root.join(&docs_dir_name),
+            docs_dir_name,
+            extra_docs_dirs: doctave_yaml
+                .extra_docs_dirs
+                .unwrap_or_default()
+                .iter()
+                .map(|name| project_root.join(name))
+                .collect(),
             title: doctave_yaml.title,
             colors: doctave_yaml
                 .colors
                 .map(|c| c.into())
                 .unwrap_or(Colors::default()),
-            logo: doctave_yaml.logo.map(|p| Link::path_to_uri_with_extension(&p)),
-            navigation: doctave_yaml.navigation.map(|n| NavRule::from_yaml_input(n)),
+            logo: doctave_yaml
+                .logo
+                .map(|p| Link::path_to_uri_with_extension(&p, &base_path)),
+            navigation,
+            named_navigation,
+            clean_urls: doctave_yaml.clean_urls.unwrap_or(true),
+            trailing_slash: doctave_yaml.trailing_slash.unwrap_or(false),
+            base_path,
+            slugify_titles: doctave_yaml.slugify_titles.unwrap_or(false),
+            nav_depth: doctave_yaml.nav_depth.filter(|d| *d > 0),
+            nav_expand_depth: doctave_yaml.nav_expand_depth.filter(|d| *d > 0),
+            nav_exclude: doctave_yaml.nav_exclude.unwrap_or_default(),
+            index_filenames: doctave_yaml
+                .index_filenames
+                .unwrap_or_else(|| vec![String::from("README.md")]),
+            nav_title_max_len: doctave_yaml.nav_title_max_len.filter(|l| *l > 0),
+            sort_descending: doctave_yaml.sort_direction.as_deref() == Some("desc"),
+            group_order: doctave_yaml
+                .group_order
+                .as_deref()
+                .and_then(GroupOrder::from_name)
+                .unwrap_or(GroupOrder::Mixed),
+            group_by_category: doctave_yaml.group_by.is_some(),
+            title_case: doctave_yaml.title_case.unwrap_or(false),
+            collapse_single: doctave_yaml.collapse_single.unwrap_or(false),
+            index_as_child: doctave_yaml.index_as_child.unwrap_or(false),
+            index_child_title: doctave_yaml
+                .index_child_title
+                .unwrap_or_else(|| String::from("Overview")),
+            show_home: doctave_yaml.show_home.unwrap_or(false),
+            home_title: doctave_yaml
+                .home_title
+                .unwrap_or_else(|| String::from("Home")),
             port: doctave_yaml.port.unwrap_or_else(|| 4001),
             build_mode: BuildMode::Dev,
+            nav_json: false,
+            summary: false,
+            single_page: false,
+            profile: None,
+            language: None,
         };
 
         Ok(config)
@@ -263,11 +958,171 @@ impl Config {
         &self.docs_dir
     }
 
+    /// Other directories whose pages are built into the site alongside the
+    /// docs directory, like generated API docs
+    pub fn extra_docs_dirs(&self) -> &[PathBuf] {
+        &self.extra_docs_dirs
+    }
+
+    /// The name of the docs directory, which paths in the navigation rules
+    /// start with
+    pub fn docs_dir_name(&self) -> &str {
+        &self.docs_dir_name
+    }
+
     /// Rules that set the site navigation structure
     pub fn navigation(&self) -> Option<&[NavRule]> {
         self.navigation.as_deref()
     }
 
+    /// Rules for one of the named navigation menus, if the navigation was
+    /// split into several of them
+    pub fn named_navigation(&self, name: &str) -> Option<&[NavRule]> {
+        self.named_navigation
+            .iter()
+            .find(|(menu, _)| menu == name)
+            .map(|(_, rules)| &rules[..])
+    }
+
+    /// The names of all the named navigation menus, in the order they are
+    /// written in doctave.yaml
+    pub fn navigation_names(&self) -> Vec<&str> {
+        self.named_navigation
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// The named navigation menu shown for pages that aren't in any of the
+    /// other menus: the one called `default`, or else the first one in
+    /// doctave.yaml
+    pub fn default_navigation_name(&self) -> Option<&str> {
+        let names = self.navigation_names();
+
+        if names.contains(&"default") {
+            Some("default")
+        } else {
+            names.first().copied()
+        }
+    }
+
+    /// Rules for the navigation shown in the sidebar by default: the single
+    /// list, or the default named menu
+    pub fn sidebar_navigation(&self) -> Option<&[NavRule]> {
+        self.navigation().or_else(|| {
+            self.default_navigation_name()
+                .and_then(|name| self.named_navigation(name))
+        })
+    }
+
+    /// Whether page URIs should have their `.html` extension stripped
+    pub fn clean_urls(&self) -> bool {
+        self.clean_urls
+    }
+
+    /// Whether URIs pointing to directory index pages should end in a `/`
+    pub fn trailing_slash(&self) -> bool {
+        self.trailing_slash
+    }
+
+    /// The path the site is served under, like `/docs`, that is put in
+    /// front of every page URI. Empty if the site is served from the root.
+    pub fn base_path(&self) -> &str {
+        &self.base_path
+    }
+
+    /// Whether pages should get their URI from their title instead of their
+    /// file name
+    pub fn slugify_titles(&self) -> bool {
+        self.slugify_titles
+    }
+
+    /// How many levels deep the generated navigation goes. `None` means
+    /// there is no limit.
+    pub fn nav_depth(&self) -> Option<usize> {
+        self.nav_depth
+    }
+
+    /// How many of the sections above the current page are expanded in the
+    /// navigation, counting up from the page. `None` means all of them.
+    pub fn nav_expand_depth(&self) -> Option<usize> {
+        self.nav_expand_depth
+    }
+
+    /// Patterns of files in the docs directory that should be left out of
+    /// the generated navigation
+    pub fn nav_exclude(&self) -> &[String] {
+        &self.nav_exclude
+    }
+
+    /// Whether the file is the index page of its directory, going by the
+    /// `index_filenames` setting
+    pub fn is_index_file(&self, path: &Path) -> bool {
+        match path.file_name() {
+            Some(name) => self.index_filenames.iter().any(|n| OsStr::new(n) == name),
+            None => false,
+        }
+    }
+
+    /// How many characters of a title are shown in the navigation before it
+    /// is cut short. `None` means titles are shown in full.
+    pub fn nav_title_max_len(&self) -> Option<usize> {
+        self.nav_title_max_len
+    }
+
+    /// Whether pages in the generated navigation should be sorted by title
+    /// in descending order
+    pub fn sort_descending(&self) -> bool {
+        self.sort_descending
+    }
+
+    /// Whether the generated navigation lists pages before directories,
+    /// after them, or sorts them together
+    pub fn group_order(&self) -> GroupOrder {
+        self.group_order
+    }
+
+    /// Whether the generated navigation should group pages by the category
+    /// set in their frontmatter, instead of by directory
+    pub fn group_by_category(&self) -> bool {
+        self.group_by_category
+    }
+
+    /// Whether titles in the generated navigation should be converted to
+    /// title case
+    pub fn title_case(&self) -> bool {
+        self.title_case
+    }
+
+    /// Whether directories with a single page should be replaced by that
+    /// page in the generated navigation
+    pub fn collapse_single(&self) -> bool {
+        self.collapse_single
+    }
+
+    /// Whether the index page of a directory should also be listed as the
+    /// first child of the directory in the generated navigation
+    pub fn index_as_child(&self) -> bool {
+        self.index_as_child
+    }
+
+    /// The title of the index page when it is listed as a child of its
+    /// directory
+    pub fn index_child_title(&self) -> &str {
+        &self.index_child_title
+    }
+
+    /// Whether a link to the landing page should be listed first in the
+    /// navigation
+    pub fn show_home(&self) -> bool {
+        self.show_home
+    }
+
+    /// The title of the link to the landing page
+    pub fn home_title(&self) -> &str {
+        &self.home_title
+    }
+
     /// Port to serve the development server on
     pub fn port(&self) -> u32 {
         self.port
@@ -289,6 +1144,55 @@ impl Config {
         self.build_mode = mode;
     }
 
+    /// Whether the navigation tree should also be written out as JSON
+    pub fn nav_json_enabled(&self) -> bool {
+        self.nav_json
+    }
+
+    pub fn enable_nav_json(&mut self) {
+        self.nav_json = true
+    }
+
+    /// Whether the navigation should also be written out as a Markdown
+    /// table of contents
+    pub fn summary_enabled(&self) -> bool {
+        self.summary
+    }
+
+    pub fn enable_summary(&mut self) {
+        self.summary = true
+    }
+
+    /// Whether all pages should also be written out together on a single
+    /// page
+    pub fn single_page_enabled(&self) -> bool {
+        self.single_page
+    }
+
+    pub fn enable_single_page(&mut self) {
+        self.single_page = true
+    }
+
+    /// The build profile, like "internal", that decides which navigation
+    /// items limited with `only` are shown
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    pub fn set_profile(&mut self, profile: &str) {
+        self.profile = Some(profile.to_owned());
+    }
+
+    /// The language directory, like "de" for `docs/de`, that the site is
+    /// built from, if the docs are split by language
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    pub fn set_language(&mut self, language: &str) {
+        self.language = Some(language.to_owned());
+    }
+
     /// The main theme color. Other shades are computed based off of this
     /// color.
     ///
@@ -330,13 +1234,55 @@ pub fn project_root() -> Option<PathBuf> {
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    extern crate indoc;
+/// Replaces every `${VAR}` in the input with the value the lookup gives for
+/// `VAR`. A `$$` stands for a literal `$`.
+fn substitute_vars(input: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find('$') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with("$$") {
+            output.push('$');
+            rest = &rest[2..];
+        } else if rest.starts_with("${") {
+            let end = rest.find('}').ok_or_else(|| {
+                Error::new(format!(
+                    "Missing closing brace for a variable in the navigation link {}",
+                    input
+                ))
+            })?;
+            let name = &rest[2..end];
 
-    #[test]
+            let value = lookup(name).ok_or_else(|| {
+                Error::new(format!(
+                    "The environment variable {} used in the navigation link {} is not set",
+                    name, input
+                ))
+            })?;
+
+            output.push_str(&value);
+            rest = &rest[end + 1..];
+        } else {
+            output.push('$');
+            rest = &rest[1..];
+        }
+    }
+
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    extern crate indoc;
+
+    #[test]
     fn validate_colors() {
         let yaml = indoc! {"
             ---
@@ -390,7 +1336,181 @@ mod test {
         assert!(
             format!("{}", error).contains(
                 "Invalid pattern for navigation children. \
-                Found 'not-wildcard', expected \"*\" or a list of child pages"
+                Found 'not-wildcard', expected \"*\", \"**\" or a list of child pages"
+            ),
+            format!("Error message was: {}", error)
+        );
+    }
+
+    #[test]
+    fn navigation_file() {
+        let nav = indoc! {"
+            ---
+            - path: docs/features
+              children:
+                - path: docs/features/markdown.md
+            - path: docs/tutorial.md
+        "};
+        let dir =
+            std::env::temp_dir().join(format!("doctave-navigation-file-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("navigation.yaml");
+        fs::write(&file, nav).unwrap();
+
+        let from_file = Config::from_yaml_str(
+            Path::new(""),
+            &format!(
+                "---\ntitle: The Title\nnavigation_file: {}\n",
+                file.display()
+            ),
+        )
+        .unwrap();
+        let inline = Config::from_yaml_str(
+            Path::new(""),
+            &format!(
+                "---\ntitle: The Title\nnavigation:\n{}",
+                nav.trim_start_matches("---\n")
+            ),
+        )
+        .unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(from_file.navigation().is_some());
+        assert_eq!(from_file.navigation(), inline.navigation());
+    }
+
+    #[test]
+    fn navigation_with_anchors() {
+        let anchored = indoc! {"
+            ---
+            title: The Title
+            navigation:
+              - path: docs/features
+                children: &features
+                  - path: docs/features/markdown.md
+                  - path: docs/features/assets.md
+              - title: Everything about features
+                children: *features
+        "};
+        let expanded = indoc! {"
+            ---
+            title: The Title
+            navigation:
+              - path: docs/features
+                children:
+                  - path: docs/features/markdown.md
+                  - path: docs/features/assets.md
+              - title: Everything about features
+                children:
+                  - path: docs/features/markdown.md
+                  - path: docs/features/assets.md
+        "};
+
+        let anchored = Config::from_yaml_str(Path::new(""), anchored).unwrap();
+        let expanded = Config::from_yaml_str(Path::new(""), expanded).unwrap();
+
+        assert!(anchored.navigation().is_some());
+        assert_eq!(anchored.navigation(), expanded.navigation());
+    }
+
+    #[test]
+    fn named_navigation() {
+        let yaml = indoc! {"
+            ---
+            title: The Title
+            navigation:
+              guides:
+                - path: docs/tutorial.md
+                - path: docs/features
+                  children: \"*\"
+              api:
+                - path: docs/configuration.md
+        "};
+
+        let config = Config::from_yaml_str(Path::new(""), yaml).unwrap();
+
+        assert_eq!(config.navigation(), None);
+        // The menus are kept in the order they are written in
+        assert_eq!(config.navigation_names(), vec!["guides", "api"]);
+        assert_eq!(config.default_navigation_name(), Some("guides"));
+        assert_eq!(
+            config.named_navigation("api"),
+            Some(
+                &[NavRule::File(
+                    PathBuf::from("docs").join("configuration.md"),
+                    LinkOptions::default()
+                )][..]
+            )
+        );
+        assert_eq!(config.named_navigation("guides").map(|n| n.len()), Some(2));
+        assert_eq!(config.named_navigation("missing"), None);
+    }
+
+    #[test]
+    fn validate_named_navigation() {
+        let yaml = indoc! {"
+            ---
+            title: The Title
+            navigation:
+              guides:
+                - path: docs/does-not-exist.md
+        "};
+
+        let error = Config::from_yaml_str(Path::new(""), yaml).unwrap_err();
+
+        assert!(
+            format!("{}", error)
+                .contains("Could not find file specified in navigation at docs/does-not-exist.md"),
+            format!("Error message was: {}", error)
+        );
+    }
+
+    #[test]
+    fn validate_sort_direction() {
+        let yaml = indoc! {"
+            ---
+            title: The Title
+            sort_direction: backwards
+        "};
+
+        let error = Config::from_yaml_str(Path::new(""), yaml).unwrap_err();
+
+        assert!(
+            format!("{}", error).contains("Found 'backwards', expected \"asc\" or \"desc\""),
+            format!("Error message was: {}", error)
+        );
+    }
+
+    #[test]
+    fn validate_group_order() {
+        let yaml = indoc! {"
+            ---
+            title: The Title
+            group_order: pages_first
+        "};
+
+        let error = Config::from_yaml_str(Path::new(""), yaml).unwrap_err();
+
+        assert!(
+            format!("{}", error).contains("Found 'pages_first', expected \"files_first\""),
+            format!("Error message was: {}", error)
+        );
+    }
+
+    #[test]
+    fn navigation_file_missing() {
+        let yaml = indoc! {"
+            ---
+            title: The Title
+            navigation_file: does-not-exist.yaml
+        "};
+
+        let error = Config::from_yaml_str(Path::new(""), yaml).unwrap_err();
+
+        assert!(
+            format!("{}", error).contains(
+                "Could not read navigation file specified in doctave.yaml at does-not-exist.yaml"
             ),
             format!("Error message was: {}", error)
         );
@@ -400,12 +1520,244 @@ mod test {
     fn convert_navigation_input_to_rules_file() {
         let input = vec![Navigation {
             path: PathBuf::from("docs").join("README.md"),
-            children: None,
+            ..Navigation::default()
+        }];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![NavRule::File(
+                PathBuf::from("docs").join("README.md"),
+                LinkOptions::default()
+            )]
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_file_with_title() {
+        let input = vec![Navigation {
+            path: PathBuf::from("docs").join("README.md"),
+            title: Some(String::from("Start here")),
+            ..Navigation::default()
+        }];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![NavRule::File(
+                PathBuf::from("docs").join("README.md"),
+                LinkOptions {
+                    title: Some(String::from("Start here")),
+                    ..LinkOptions::default()
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_icons() {
+        let input = vec![
+            Navigation {
+                path: PathBuf::from("docs").join("README.md"),
+                icon: Some(String::from("📖")),
+                ..Navigation::default()
+            },
+            Navigation {
+                path: PathBuf::from("docs").join("features"),
+                icon: Some(String::from("🔧")),
+                ..Navigation::default()
+            },
+        ];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![
+                NavRule::File(
+                    PathBuf::from("docs").join("README.md"),
+                    LinkOptions {
+                        icon: Some(String::from("📖")),
+                        ..LinkOptions::default()
+                    }
+                ),
+                NavRule::Dir(
+                    PathBuf::from("docs").join("features"),
+                    None,
+                    DirOptions {
+                        link: LinkOptions {
+                            icon: Some(String::from("🔧")),
+                            ..LinkOptions::default()
+                        },
+                        ..DirOptions::default()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_classes() {
+        let input = vec![
+            Navigation {
+                path: PathBuf::from("docs").join("tutorial.md"),
+                class: Some(String::from("highlight  wide")),
+                ..Navigation::default()
+            },
+            Navigation {
+                url: Some(String::from("https://example.com")),
+                title: Some(String::from("Example")),
+                class: Some(String::from("external")),
+                ..Navigation::default()
+            },
+        ];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![
+                NavRule::File(
+                    PathBuf::from("docs").join("tutorial.md"),
+                    LinkOptions {
+                        classes: vec![String::from("highlight"), String::from("wide")],
+                        ..LinkOptions::default()
+                    }
+                ),
+                NavRule::Link {
+                    title: String::from("Example"),
+                    url: String::from("https://example.com"),
+                    target: None,
+                    classes: vec![String::from("external")],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_directory_title() {
+        let input = vec![Navigation {
+            path: PathBuf::from("docs").join("features"),
+            title: Some(String::from("All Features")),
+            children: Some(NavChildren::WildCard(String::from("*"))),
+            ..Navigation::default()
+        }];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![NavRule::Dir(
+                PathBuf::from("docs").join("features"),
+                Some(DirIncludeRule::WildCard),
+                DirOptions {
+                    link: LinkOptions {
+                        title: Some(String::from("All Features")),
+                        ..LinkOptions::default()
+                    },
+                    ..DirOptions::default()
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_divider() {
+        let input = vec![
+            Navigation {
+                path: PathBuf::from("docs").join("README.md"),
+                ..Navigation::default()
+            },
+            Navigation {
+                divider: Some(true),
+                ..Navigation::default()
+            },
+            Navigation {
+                path: PathBuf::from("docs").join("tutorial.md"),
+                ..Navigation::default()
+            },
+        ];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![
+                NavRule::File(
+                    PathBuf::from("docs").join("README.md"),
+                    LinkOptions::default()
+                ),
+                NavRule::Divider,
+                NavRule::File(
+                    PathBuf::from("docs").join("tutorial.md"),
+                    LinkOptions::default()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_external_link() {
+        let input = vec![Navigation {
+            title: Some(String::from("GitHub")),
+            url: Some(String::from("https://github.com/Doctave/doctave")),
+            ..Navigation::default()
+        }];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![NavRule::Link {
+                title: String::from("GitHub"),
+                url: String::from("https://github.com/Doctave/doctave"),
+                target: None,
+                classes: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn validate_navigation_external_link_without_title() {
+        let yaml = indoc! {"
+            ---
+            title: The Title
+            navigation:
+              - url: https://github.com/Doctave/doctave
+        "};
+
+        let error = Config::from_yaml_str(Path::new(""), yaml).unwrap_err();
+
+        assert!(
+            format!("{}", error)
+                .contains("Missing title for external link https://github.com/Doctave/doctave"),
+            format!("Error message was: {}", error)
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_group() {
+        let input = vec![Navigation {
+            title: Some(String::from("Guides")),
+            children: Some(NavChildren::List(vec![Navigation {
+                path: PathBuf::from("docs").join("README.md"),
+                ..Navigation::default()
+            }])),
+            ..Navigation::default()
+        }];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![NavRule::Group {
+                title: String::from("Guides"),
+                children: vec![NavRule::File(
+                    PathBuf::from("docs").join("README.md"),
+                    LinkOptions::default()
+                )],
+            }]
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_glob() {
+        let input = vec![Navigation {
+            path: PathBuf::from("docs").join("features").join("*.md"),
+            ..Navigation::default()
         }];
 
         assert_eq!(
             NavRule::from_yaml_input(input),
-            vec![NavRule::File(PathBuf::from("docs").join("README.md"))]
+            vec![NavRule::Glob(
+                PathBuf::from("docs").join("features").join("*.md")
+            )]
         );
     }
 
@@ -413,14 +1765,36 @@ mod test {
     fn convert_navigation_input_to_rules_directory_no_children() {
         let input = vec![Navigation {
             path: PathBuf::from("docs").join("features"), // TODO: Make not rely on our docs
-            children: None,
+            ..Navigation::default()
+        }];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![NavRule::Dir(
+                PathBuf::from("docs").join("features"),
+                None,
+                DirOptions::default()
+            )]
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_directory_collapsed() {
+        let input = vec![Navigation {
+            path: PathBuf::from("docs").join("features"), // TODO: Make not rely on our docs
+            collapsed: Some(true),
+            ..Navigation::default()
         }];
 
         assert_eq!(
             NavRule::from_yaml_input(input),
             vec![NavRule::Dir(
                 PathBuf::from("docs").join("features"),
-                None
+                None,
+                DirOptions {
+                    collapsed: true,
+                    ..DirOptions::default()
+                }
             )]
         );
     }
@@ -430,13 +1804,33 @@ mod test {
         let input = vec![Navigation {
             path: PathBuf::from("docs").join("features"), // TODO: Make not rely on our docs
             children: Some(NavChildren::WildCard(String::from("*"))),
+            ..Navigation::default()
+        }];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![NavRule::Dir(
+                PathBuf::from("docs").join("features"),
+                Some(DirIncludeRule::WildCard),
+                DirOptions::default()
+            )]
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_directory_deep_wildcard_children() {
+        let input = vec![Navigation {
+            path: PathBuf::from("docs").join("features"),
+            children: Some(NavChildren::WildCard(String::from("**"))),
+            ..Navigation::default()
         }];
 
         assert_eq!(
             NavRule::from_yaml_input(input),
             vec![NavRule::Dir(
                 PathBuf::from("docs").join("features"),
-                Some(DirIncludeRule::WildCard)
+                Some(DirIncludeRule::DeepWildCard),
+                DirOptions::default()
             )]
         );
     }
@@ -449,8 +1843,9 @@ mod test {
                 path: PathBuf::from("docs")
                     .join("features")
                     .join("markdown.md"),
-                children: None,
+                ..Navigation::default()
             }])),
+            ..Navigation::default()
         }];
 
         assert_eq!(
@@ -458,11 +1853,270 @@ mod test {
             vec![NavRule::Dir(
                 PathBuf::from("docs").join("features"),
                 Some(DirIncludeRule::Explicit(vec![NavRule::File(
-                    PathBuf::from("docs")
-                        .join("features")
-                        .join("markdown.md")
-                )]))
+                    PathBuf::from("docs").join("features").join("markdown.md"),
+                    LinkOptions::default()
+                )])),
+                DirOptions::default()
+            )]
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_directory_wildcard_except() {
+        let mut patterns = BTreeMap::new();
+        patterns.insert(
+            String::from("*"),
+            NavExcept {
+                except: vec![PathBuf::from("markdown.md")],
+            },
+        );
+
+        let input = vec![Navigation {
+            path: PathBuf::from("docs").join("features"),
+            children: Some(NavChildren::Except(patterns)),
+            ..Navigation::default()
+        }];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![NavRule::Dir(
+                PathBuf::from("docs").join("features"),
+                Some(DirIncludeRule::Except(vec![PathBuf::from("docs")
+                    .join("features")
+                    .join("markdown.md")])),
+                DirOptions::default()
+            )]
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_directory_overview() {
+        let input = vec![Navigation {
+            path: PathBuf::from("docs").join("features"),
+            include_overview: Some(String::from("Overview")),
+            children: Some(NavChildren::WildCard(String::from("*"))),
+            ..Navigation::default()
+        }];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![NavRule::Dir(
+                PathBuf::from("docs").join("features"),
+                Some(DirIncludeRule::WildCard),
+                DirOptions {
+                    overview: Some(String::from("Overview")),
+                    ..DirOptions::default()
+                }
             )]
         );
     }
+
+    #[test]
+    fn substitute_set_variable() {
+        let lookup = |name: &str| match name {
+            "REPO_URL" => Some(String::from("https://github.com/Doctave/doctave")),
+            _ => None,
+        };
+
+        assert_eq!(
+            substitute_vars("${REPO_URL}/issues", lookup).unwrap(),
+            "https://github.com/Doctave/doctave/issues"
+        );
+    }
+
+    #[test]
+    fn substitute_unset_variable() {
+        let error = substitute_vars("${REPO_URL}/issues", |_| None).unwrap_err();
+
+        assert!(
+            format!("{}", error).contains("environment variable REPO_URL"),
+            "Error message was: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn substitute_escaped_dollar() {
+        assert_eq!(
+            substitute_vars("https://example.com/$${NAME}/$5", |_| None).unwrap(),
+            "https://example.com/${NAME}/$5"
+        );
+    }
+
+    #[test]
+    fn validate_group_by() {
+        let yaml = indoc! {"
+            ---
+            title: The Title
+            group_by: author
+        "};
+
+        let error = Config::from_yaml_str(Path::new(""), yaml).unwrap_err();
+
+        assert!(
+            format!("{}", error).contains("Found 'author', expected \"category\""),
+            "Error message was: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn convert_navigation_input_to_rules_auto() {
+        let input = vec![
+            Navigation {
+                path: PathBuf::from("docs").join("tutorial.md"),
+                ..Navigation::default()
+            },
+            Navigation {
+                auto: Some(true),
+                ..Navigation::default()
+            },
+        ];
+
+        assert_eq!(
+            NavRule::from_yaml_input(input),
+            vec![
+                NavRule::File(
+                    PathBuf::from("docs").join("tutorial.md"),
+                    LinkOptions::default()
+                ),
+                NavRule::Auto,
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_navigation_inside_docs() {
+        let yaml = indoc! {"
+            ---
+            title: The Title
+            navigation:
+              - path: docs/tutorial.md
+        "};
+
+        assert!(Config::from_yaml_str(Path::new(""), yaml).is_ok());
+    }
+
+    #[test]
+    fn validate_navigation_outside_docs() {
+        let yaml = indoc! {"
+            ---
+            title: The Title
+            navigation:
+              - path: features/markdown.md
+        "};
+
+        let error = Config::from_yaml_str(Path::new(""), yaml).unwrap_err();
+
+        assert!(
+            format!("{}", error).contains("like docs/features/markdown.md"),
+            "Error message was: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn navigation_paths_in_docs_dir() {
+        assert!(NavRule::in_docs_dir(
+            Path::new("docs/child/three.md"),
+            "docs"
+        ));
+        assert!(NavRule::in_docs_dir(Path::new("docs"), "docs"));
+        assert!(!NavRule::in_docs_dir(Path::new("child/three.md"), "docs"));
+        assert!(!NavRule::in_docs_dir(
+            Path::new("/docs/child/three.md"),
+            "docs"
+        ));
+        assert!(NavRule::in_docs_dir(Path::new("content/one.md"), "content"));
+    }
+
+    #[test]
+    fn docs_dir_name() {
+        let yaml = indoc! {"
+            ---
+            title: The Title
+            docs_dir: content
+        "};
+
+        let config = Config::from_yaml_str(Path::new("project"), yaml).unwrap();
+
+        assert_eq!(config.docs_dir(), Path::new("project").join("content"));
+        assert_eq!(config.docs_dir_name(), "content");
+    }
+
+    #[test]
+    fn navigation_path_shorthand() {
+        let shorthand = Config::from_yaml_str(
+            Path::new(""),
+            indoc! {"
+                ---
+                title: The Title
+                navigation:
+                  - docs/README.md
+                  - path: docs/features
+                    children:
+                      - docs/features/markdown.md
+                      - path: docs/features/assets.md
+                        title: Assets
+            "},
+        )
+        .unwrap();
+        let full = Config::from_yaml_str(
+            Path::new(""),
+            indoc! {"
+                ---
+                title: The Title
+                navigation:
+                  - path: docs/README.md
+                  - path: docs/features
+                    children:
+                      - path: docs/features/markdown.md
+                      - path: docs/features/assets.md
+                        title: Assets
+            "},
+        )
+        .unwrap();
+
+        assert!(shorthand.navigation().is_some());
+        assert_eq!(shorthand.navigation(), full.navigation());
+        assert_eq!(
+            shorthand.navigation().unwrap()[0],
+            NavRule::File(PathBuf::from("docs/README.md"), LinkOptions::default())
+        );
+    }
+
+    #[test]
+    fn named_navigation_path_shorthand() {
+        let config = Config::from_yaml_str(
+            Path::new(""),
+            indoc! {"
+                ---
+                title: The Title
+                navigation:
+                  guides:
+                    - docs/README.md
+                    - path: docs/tutorial.md
+            "},
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.named_navigation("guides").map(|rules| rules.len()),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn extra_docs_dirs_invalid() {
+        let yaml = indoc! {"
+            ---
+            title: The Title
+            extra_docs_dirs:
+              - docs
+        "};
+
+        let error = Config::from_yaml_str(Path::new(""), yaml).unwrap_err();
+
+        assert!(format!("{}", error).contains("Invalid extra_docs_dirs in doctave.yaml"));
+    }
 }
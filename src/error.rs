@@ -1,5 +1,7 @@
 use std::fmt;
 
+use crate::navigation::NavigationError;
+
 #[derive(Debug)]
 pub struct Error {
     kind: ErrorKind,
@@ -62,3 +64,21 @@ impl From<std::io::Error>  for Error {
         Error::io(other, "IO error occurred")
     }
 }
+
+impl From<NavigationError> for Error {
+    fn from(other: NavigationError) -> Error {
+        // Only some of the problems come from the rules in doctave.yaml.
+        // The others are caused by the files in the docs directories.
+        let prefix = match other {
+            NavigationError::NotFound { .. }
+            | NavigationError::Cycle { .. }
+            | NavigationError::UnknownMenu { .. } => "Invalid navigation in doctave.yaml",
+            NavigationError::Collision { .. } | NavigationError::DuplicateUri { .. } => {
+                "Conflicting pages in the docs"
+            }
+            NavigationError::UnknownLanguage { .. } => "Invalid language",
+        };
+
+        Error::new(format!("{}:\n{}", prefix, other))
+    }
+}
@@ -25,6 +25,7 @@ use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
 pub use config::Config;
 pub use build::BuildCommand;
@@ -35,7 +36,7 @@ pub use site::BuildMode;
 
 pub use doctave_markdown::{Heading, Markdown};
 use handlebars::Handlebars;
-use navigation::Link;
+use navigation::{Link, Pin};
 
 static APP_JS: &str = include_str!("assets/app.js");
 static MERMAID_JS: &str = include_str!("assets/mermaid.min.js");
@@ -66,6 +67,12 @@ lazy_static! {
                 include_str!("../templates/nested_navigation.html"),
             )
             .unwrap();
+        handlebars
+            .register_template_string(
+                "navigation_link",
+                include_str!("../templates/navigation_link.html"),
+            )
+            .unwrap();
         handlebars
             .register_template_string("style.css", include_str!("../templates/style.css"))
             .unwrap();
@@ -98,12 +105,29 @@ impl Directory {
             .to_path_buf()
     }
 
-    fn index(&self) -> &Document {
-        &self
-            .docs
-            .iter()
-            .find(|d| d.original_file_name() == Some(OsStr::new("README.md")))
-            .expect("No index file found for directory")
+    fn index(&self) -> Option<&Document> {
+        self.docs.iter().find(|d| d.is_index())
+    }
+
+    /// The file and directory names listed in the `.order` file of the
+    /// directory, one per line, if it has one. Blank lines are skipped.
+    fn order(&self) -> Option<Vec<String>> {
+        let raw = fs::read_to_string(self.path.join(".order")).ok()?;
+
+        Some(
+            raw.lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty())
+                .map(|line| line.to_owned())
+                .collect(),
+        )
+    }
+
+    /// Whether the directory holds a document or directory with this name
+    fn contains(&self, name: &str) -> bool {
+        let named = |path: &Path| path.file_name() == Some(OsStr::new(name));
+
+        self.docs.iter().any(|d| named(&d.path)) || self.dirs.iter().any(|d| named(&d.path))
     }
 }
 
@@ -117,9 +141,18 @@ struct Document {
     /// The relative path in the docs folder to the file
     path: PathBuf,
     rename: Option<String>,
+    /// Whether the document is the index page of its directory
+    index: bool,
+    /// Whether Doctave made up the document, like the index page of a
+    /// directory without a `README.md`, instead of reading it from a file
+    generated: bool,
     raw: String,
     markdown: Markdown,
     frontmatter: BTreeMap<String, String>,
+    title: String,
+    /// The date of the page as (year, month, day). Taken from the `date`
+    /// frontmatter key, or from git when the page is sorted by date.
+    date: Option<(u32, u32, u32)>,
 }
 
 impl Document {
@@ -135,15 +168,23 @@ impl Document {
         Document::new(relative_docs_path, raw, frontmatter)
     }
 
-    /// Creates a new document from its raw components
+    /// Creates a new document from its raw components. A `README.md` is
+    /// the index of its directory, unless changed with `set_index`.
     fn new(path: &Path, raw: String, frontmatter: BTreeMap<String, String>) -> Self {
-        let rename = if path.ends_with("README.md") {
+        let index = path.ends_with("README.md");
+        let rename = if index {
             Some("index".to_string())
         } else {
-            None
+            frontmatter.get("slug").cloned()
         };
 
         let markdown = doctave_markdown::parse(frontmatter::without(&raw));
+        let date = frontmatter.get("date").and_then(|d| parse_date(d));
+        let title = frontmatter
+            .get("title")
+            .cloned()
+            .or_else(|| first_heading(frontmatter::without(&raw)))
+            .unwrap_or_else(|| humanize(path.file_stem().unwrap().to_str().unwrap()));
 
         Document {
             id: DOCUMENT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
@@ -151,12 +192,46 @@ impl Document {
             raw,
             markdown,
             rename,
+            index,
+            generated: false,
             frontmatter,
+            title,
+            date,
         }
     }
 
-    fn original_file_name(&self) -> Option<&OsStr> {
-        self.path.file_name()
+    /// Whether the document is the index page of its directory
+    fn is_index(&self) -> bool {
+        self.index
+    }
+
+    /// Creates the index page Doctave generates for a directory without one
+    fn generated_index(path: &Path, raw: String, frontmatter: BTreeMap<String, String>) -> Self {
+        Document {
+            generated: true,
+            ..Document::new(path, raw, frontmatter)
+        }
+    }
+
+    /// Whether Doctave generated the document instead of reading it from a
+    /// file
+    fn is_generated(&self) -> bool {
+        self.generated
+    }
+
+    /// Makes the document the index page of its directory, served from the
+    /// directory's URI, or turns it back into a regular page.
+    fn set_index(&mut self, index: bool) {
+        if index == self.index {
+            return;
+        }
+
+        self.index = index;
+        self.rename = if index {
+            Some("index".to_string())
+        } else {
+            self.frontmatter.get("slug").cloned()
+        };
     }
 
     fn destination(&self, out: &Path) -> PathBuf {
@@ -174,8 +249,83 @@ impl Document {
         }
     }
 
-    fn uri_path(&self) -> String {
-        Link::path_to_uri(&self.html_path())
+    fn uri_path(&self, config: &Config) -> String {
+        Link::path_to_uri(&self.html_path(), config)
+    }
+
+    /// Checks that the values set in the frontmatter can be used to build
+    /// the page
+    fn validate(&self) -> Result<()> {
+        if let Some(slug) = self.frontmatter.get("slug") {
+            let url_safe = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+
+            if slug.is_empty() || !slug.chars().all(url_safe) {
+                return Err(Error::new(format!(
+                    "Invalid slug '{}' in {}.\n\
+                     Slugs may only contain letters, numbers, dashes and underscores.",
+                    slug,
+                    self.path.display()
+                )));
+            }
+        }
+
+        if let Some(pin) = self.frontmatter.get("pin") {
+            if Pin::from_name(pin).is_none() {
+                return Err(Error::new(format!(
+                    "Invalid pin '{}' in {}.\n\
+                     Expected \"top\" or \"bottom\".",
+                    pin,
+                    self.path.display()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The URI the document would have if it wasn't given a slug
+    fn source_uri_path(&self, config: &Config) -> String {
+        if self.index {
+            self.uri_path(config)
+        } else {
+            Link::path_to_uri(&self.path.with_extension("html"), config)
+        }
+    }
+
+    /// Uses a slug of the title as the file name of the page, unless the
+    /// page already has a slug or is an index page.
+    fn slugify_title(&mut self) {
+        if self.rename.is_none() {
+            let slug = Link::slugify(self.title());
+
+            if !slug.is_empty() {
+                self.rename = Some(slug);
+            }
+        }
+    }
+
+    /// Falls back to the date of the last git commit touching the file, if
+    /// the frontmatter didn't set a date. Files outside of a git repository
+    /// are left without one.
+    fn load_git_date(&mut self, absolute_path: &Path) {
+        if self.date.is_some() {
+            return;
+        }
+
+        let output = Command::new("git")
+            .arg("log")
+            .arg("-1")
+            .arg("--format=%cI")
+            .arg("--")
+            .arg(absolute_path.file_name().unwrap())
+            .current_dir(absolute_path.parent().unwrap())
+            .output();
+
+        if let Ok(output) = output {
+            if output.status.success() {
+                self.date = parse_date(&String::from_utf8_lossy(&output.stdout));
+            }
+        }
     }
 
     fn markdown_section(&self) -> &str {
@@ -190,10 +340,158 @@ impl Document {
         &self.markdown.as_html
     }
 
+    /// The title of the document. Taken from the `title` frontmatter key,
+    /// falling back to the first `# Heading` in the page, and finally to
+    /// the file name.
     fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// A shorter title to use in the navigation, if set in the frontmatter
+    fn nav_title(&self) -> Option<&str> {
+        self.frontmatter.get("nav_title").map(|t| t.as_str())
+    }
+
+    /// The position of the document in the navigation, if set in the
+    /// frontmatter.
+    fn order(&self) -> Option<i64> {
+        self.frontmatter.get("order").and_then(|o| o.parse().ok())
+    }
+
+    /// An icon shown next to the document in the navigation, if set in the
+    /// frontmatter.
+    fn icon(&self) -> Option<&str> {
+        self.frontmatter.get("icon").map(|i| i.as_str())
+    }
+
+    /// A short summary of the document, if set in the frontmatter
+    fn description(&self) -> Option<&str> {
+        self.frontmatter.get("description").map(|d| d.as_str())
+    }
+
+    /// Labels like "New" or "Beta" shown next to the document in the
+    /// navigation, given as a comma separated list in the frontmatter
+    fn badges(&self) -> Vec<String> {
         self.frontmatter
-            .get("title")
-            .map(|t| t.as_ref())
-            .unwrap_or_else(|| self.path.file_stem().unwrap().to_str().unwrap())
+            .get("badges")
+            .map(|b| {
+                b.split(',')
+                    .map(|badge| badge.trim().to_owned())
+                    .filter(|badge| !badge.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Paths of other pages to link to as related reading, given as a comma
+    /// separated list in the frontmatter. Paths start with the docs
+    /// directory, like in the navigation rules.
+    fn related(&self) -> Vec<PathBuf> {
+        self.frontmatter
+            .get("related")
+            .map(|r| {
+                r.split(',')
+                    .map(|path| path.trim())
+                    .filter(|path| !path.is_empty())
+                    .map(PathBuf::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The category the document is grouped under in the navigation, if
+    /// set in the frontmatter
+    fn category(&self) -> Option<&str> {
+        self.frontmatter.get("category").map(|c| c.as_str())
+    }
+
+    /// Whether the document is kept at the top or bottom of its level in
+    /// the navigation, if set in the frontmatter
+    fn pin(&self) -> Option<Pin> {
+        self.frontmatter.get("pin").and_then(|p| Pin::from_name(p))
+    }
+
+    fn date(&self) -> Option<(u32, u32, u32)> {
+        self.date
+    }
+
+    /// How the pages of the directory this document is the index of should
+    /// be sorted, if set in the frontmatter.
+    fn sort(&self) -> Option<&str> {
+        self.frontmatter.get("sort").map(|s| s.as_str())
+    }
+
+    /// Whether the document should be left out of the navigation. The page
+    /// itself is still built.
+    fn hidden(&self) -> bool {
+        self.frontmatter
+            .get("hidden")
+            .map(|h| h == "true")
+            .unwrap_or(false)
+    }
+
+    /// Whether the document is marked as a draft, with `status: draft`
+    fn is_draft(&self) -> bool {
+        self.frontmatter
+            .get("status")
+            .map(|s| s == "draft")
+            .unwrap_or(false)
+    }
+}
+
+/// Parses the date part of a `YYYY-MM-DD` string. Anything following the
+/// day, like the time in an ISO 8601 timestamp, is ignored.
+fn parse_date(raw: &str) -> Option<(u32, u32, u32)> {
+    let raw = raw.trim();
+    let mut parts = raw.get(..10)?.splitn(3, '-');
+
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+
+    if (1..=12).contains(&month) && (1..=31).contains(&day) {
+        Some((year, month, day))
+    } else {
+        None
+    }
+}
+
+/// Finds the text of the first top level ATX heading in the Markdown, like
+/// `# Introduction`. Headings inside fenced code blocks are skipped.
+fn first_heading(markdown: &str) -> Option<String> {
+    let mut in_code_block = false;
+
+    for line in markdown.lines() {
+        let line = line.trim_start();
+
+        if line.starts_with("```") || line.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        if in_code_block {
+            continue;
+        }
+
+        if line == "#" || line.starts_with("# ") || line.starts_with("#\t") {
+            let heading = line[1..].trim().trim_end_matches('#').trim_end();
+
+            if !heading.is_empty() {
+                return Some(heading.to_string());
+            }
+        }
+    }
+
+    None
+}
+
+/// Turns a file name like `getting-started` into `Getting started`
+fn humanize(file_stem: &str) -> String {
+    let words = file_stem.replace(|c| c == '-' || c == '_', " ");
+    let mut chars = words.trim().chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => file_stem.to_string(),
     }
 }
@@ -21,6 +21,35 @@ fn main() {
                     Arg::with_name("release")
                         .long("release")
                         .help("Build the site in release mode"),
+                )
+                .arg(
+                    Arg::with_name("nav-json")
+                        .long("nav-json")
+                        .help("Also write the navigation tree as JSON to nav.json"),
+                )
+                .arg(
+                    Arg::with_name("summary")
+                        .long("summary")
+                        .help("Also write the navigation as a Markdown list to SUMMARY.md"),
+                )
+                .arg(
+                    Arg::with_name("single-page")
+                        .long("single-page")
+                        .help("Also write every page into print.html, in reading order"),
+                )
+                .arg(
+                    Arg::with_name("profile")
+                        .long("profile")
+                        .takes_value(true)
+                        .value_name("PROFILE")
+                        .help("Build profile that decides which navigation items are shown"),
+                )
+                .arg(
+                    Arg::with_name("language")
+                        .long("language")
+                        .takes_value(true)
+                        .value_name("LANGUAGE")
+                        .help("Language directory in the docs folder to build the site from"),
                 ),
         )
         .subcommand(
@@ -75,6 +104,26 @@ fn build(cmd: &ArgMatches) -> doctave::Result<()> {
         config.set_build_mode(doctave::BuildMode::Release);
     }
 
+    if cmd.is_present("nav-json") {
+        config.enable_nav_json();
+    }
+
+    if cmd.is_present("summary") {
+        config.enable_summary();
+    }
+
+    if cmd.is_present("single-page") {
+        config.enable_single_page();
+    }
+
+    if let Some(profile) = cmd.value_of("profile") {
+        config.set_profile(profile);
+    }
+
+    if let Some(language) = cmd.value_of("language") {
+        config.set_language(language);
+    }
+
     if cmd.is_present("no-color") {
         config.disable_colors();
     }
@@ -1,552 +1,7766 @@
-use crate::config::{Config, DirIncludeRule, NavRule};
-use crate::Directory;
+use crate::config::{Config, DirIncludeRule, DirOptions, LinkOptions, NavRule};
+use crate::{BuildMode, Directory, Document};
+use rayon::prelude::*;
 use serde::Serialize;
 
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
-use std::path::{Path, PathBuf};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
 
 pub struct Navigation<'a> {
     config: &'a Config,
+    /// The language directory the navigation is built for. Its name is
+    /// left out of the URIs, so that `docs/en/guide.md` becomes `/guide`.
+    language: Option<String>,
+    /// The build profile deciding which rules limited to some profiles
+    /// apply
+    profile: Option<String>,
+    /// Whether pages marked `status: draft` are listed. Only done when
+    /// previewing the site locally by default.
+    include_drafts: bool,
+    /// Problems found while building the navigation that don't stop the
+    /// build, but that the user should know about.
+    warnings: Mutex<Vec<String>>,
+    /// The last tree built. Shared with the site, so that it survives
+    /// between rebuilds.
+    cache: Arc<NavigationCache>,
+}
+
+/// The last navigation tree built, along with the fingerprint of the
+/// directory it was built from and the warnings found while building it.
+#[derive(Default)]
+pub struct NavigationCache {
+    entry: Mutex<Option<(u64, Vec<Link>, Vec<String>)>>,
+    hits: AtomicUsize,
+}
+
+impl NavigationCache {
+    /// The last tree built, if any
+    pub fn links(&self) -> Option<Vec<Link>> {
+        self.entry
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(_, links, _)| links.clone())
+    }
+
+    /// How many times a cached tree was used instead of building a new one
+    #[cfg(test)]
+    pub fn hits(&self) -> usize {
+        self.hits.load(AtomicOrdering::SeqCst)
+    }
 }
 
 impl<'a> Navigation<'a> {
     pub fn new(config: &'a Config) -> Self {
-        Navigation { config }
+        Navigation {
+            config,
+            language: None,
+            profile: config.profile().map(|p| p.to_owned()),
+            include_drafts: config.build_mode() == BuildMode::Dev,
+            warnings: Mutex::new(vec![]),
+            cache: Arc::new(NavigationCache::default()),
+        }
     }
 
-    /// Builds a navigation tree given a root directory
-    pub fn build_for(&self, dir: &Directory) -> Vec<Link> {
-        let default: Vec<Link> = dir.into();
+    /// Keeps the built trees in the given cache instead of one owned by
+    /// this navigation, so that they can be reused by later builds.
+    pub fn with_cache(mut self, cache: Arc<NavigationCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Builds the navigation for a single language directory, like
+    /// `docs/en`, leaving the language out of the URIs.
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_owned());
+        self
+    }
+
+    /// Returns the warnings collected while building the navigation, and
+    /// clears them.
+    pub fn take_warnings(&self) -> Vec<String> {
+        std::mem::take(&mut *self.warnings.lock().unwrap())
+    }
+
+    fn warn(&self, message: String) {
+        self.warnings.lock().unwrap().push(message);
+    }
 
-        match &self.config.navigation() {
-            None => default,
-            Some(nav) => self.customize(nav, &default),
+    /// A navigation with the same settings but its own warnings, for
+    /// building part of the tree on another thread
+    fn scoped(&self) -> Navigation<'a> {
+        Navigation {
+            config: self.config,
+            language: self.language.clone(),
+            profile: self.profile.clone(),
+            include_drafts: self.include_drafts,
+            warnings: Mutex::new(vec![]),
+            cache: self.cache.clone(),
         }
     }
 
-    /// Customizes the navigation tree given some rules provided through the
-    /// doctave.yaml config.
-    ///
-    /// Note that the config validates that any files/directories referenced
-    /// in the rules already exist, which is why we can reasonably confidently
-    /// unwrap some Nones here. The only case they would trip is if the files
-    /// got removed between the validation and building these rules, which is
-    /// a _very_ small window.
-    ///
-    /// Note that in the case where an explicit path is provided, the link is
-    /// not necessarily a direct child of its parent. It could be that links
-    /// under a directory actually point to a parent's sibling, or to somewhere
-    /// else in the tree.
-    fn customize(&self, rules: &[NavRule], default: &[Link]) -> Vec<Link> {
-        let mut links = vec![];
+    /// Builds a navigation tree given a root directory. Only the content
+    /// of pages changes between most rebuilds, so the tree is reused as long
+    /// as the paths, titles and frontmatter in the directory stay the same.
+    pub fn build_for(&self, dir: &Directory) -> Result<Vec<Link>, NavigationError> {
+        let fingerprint = self.fingerprint(dir);
 
-        for rule in rules {
-            match rule {
-                NavRule::File(path) => links.push(
-                    self.find_matching_link(path, &default)
-                        .expect("No matching link found"),
-                ),
-                NavRule::Dir(path, dir_rule) => {
-                    let mut index_link = self
-                        .find_matching_link(path, &default)
-                        .expect("No matching link found");
+        if let Some((cached, links, warnings)) = &*self.cache.entry.lock().unwrap() {
+            if *cached == fingerprint {
+                self.cache.hits.fetch_add(1, AtomicOrdering::SeqCst);
+                self.warnings
+                    .lock()
+                    .unwrap()
+                    .extend(warnings.iter().cloned());
 
-                    match dir_rule {
-                        // Don't include any children
-                        None => {
-                            index_link.children.truncate(0);
-                            links.push(index_link);
-                        }
-                        // Include all children
-                        Some(DirIncludeRule::WildCard) => links.push(index_link),
-                        // Include only links that match the description
-                        Some(DirIncludeRule::Explicit(nested_rules)) => {
-                            let children = self.customize(nested_rules, &default);
-                            index_link.children = children;
-                            links.push(index_link);
-                        }
-                    }
-                }
+                return Ok(links.clone());
             }
         }
 
-        links
+        let earlier_warnings = self.warnings.lock().unwrap().len();
+        let links = self.build_uncached(dir)?;
+        let warnings = self.warnings.lock().unwrap()[earlier_warnings..].to_vec();
+
+        *self.cache.entry.lock().unwrap() = Some((fingerprint, links.clone(), warnings));
+
+        Ok(links)
     }
 
-    /// Matches a path provided in a NavRule to a Link. Recursively searches through
-    /// the link children to find a match.
-    fn find_matching_link(&self, path: &Path, links: &[Link]) -> Option<Link> {
-        let search_result = links.iter().find(|link| {
-            let mut without_docs_part = path.components();
-            let _ = without_docs_part.next();
+    /// A hash of everything in the directory the navigation is built from,
+    /// and of the settings of this navigation that change the tree
+    fn fingerprint(&self, dir: &Directory) -> u64 {
+        fn hash_dir(dir: &Directory, hasher: &mut DefaultHasher) {
+            dir.path.hash(hasher);
 
-            link.path == Link::path_to_uri(without_docs_part.as_path())
-        });
+            for doc in &dir.docs {
+                doc.path.hash(hasher);
+                doc.title.hash(hasher);
+                doc.frontmatter.hash(hasher);
+                doc.date.hash(hasher);
+                doc.generated.hash(hasher);
+            }
+
+            dir.order().hash(hasher);
+
+            for child in &dir.dirs {
+                hash_dir(child, hasher);
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        self.language.hash(&mut hasher);
+        self.profile.hash(&mut hasher);
+        self.include_drafts.hash(&mut hasher);
+        hash_dir(dir, &mut hasher);
+        hasher.finish()
+    }
+
+    fn build_uncached(&self, dir: &Directory) -> Result<Vec<Link>, NavigationError> {
+        self.validate(dir)?;
+
+        let mut default = self.default_links(dir);
 
-        match search_result {
-            Some(link) => Some(link.clone()),
+        let mut links = match self.config.sidebar_navigation() {
+            None if self.config.group_by_category() => self.category_links(dir),
             None => {
-                let recursive_results = links
-                    .iter()
-                    .flat_map(|l| self.find_matching_link(path, &l.children))
-                    .collect::<Vec<_>>();
+                if let Some(depth) = self.config.nav_depth() {
+                    Self::limit_depth(&mut default, depth);
+                }
 
-                // _Should_ only be one match, if any
-                return recursive_results.get(0).map(|l| l.clone());
+                default
             }
+            Some(nav) => self.customize(nav, &default)?,
+        };
+
+        // The root README is the landing page, and is otherwise only
+        // reachable through the site title.
+        if self.config.show_home() {
+            links.insert(
+                0,
+                Link {
+                    title: self.config.home_title().to_owned(),
+                    path: Link::root_uri(self.config),
+                    source_path: dir.index().map(|index| self.source_path(index)),
+                    ..Link::default()
+                },
+            );
         }
+
+        Ok(links)
     }
-}
 
-impl From<&Directory> for Vec<Link> {
-    fn from(dir: &Directory) -> Vec<Link> {
-        let mut links = dir
-            .docs
+    /// Builds a navigation tree from a flat list of documents, for tests
+    /// that would rather not put the directories together by hand. The
+    /// directories are made up from the paths of the documents inside the
+    /// docs directory.
+    #[cfg(test)]
+    pub(crate) fn build_from_docs(&self, docs: &[Document]) -> Result<Vec<Link>, NavigationError> {
+        let root = Self::directory_from_docs(self.config.docs_dir(), Path::new(""), docs.iter());
+
+        self.build_for(&root)
+    }
+
+    #[cfg(test)]
+    fn directory_from_docs<'d, I>(path: &Path, prefix: &Path, docs: I) -> Directory
+    where
+        I: Iterator<Item = &'d Document>,
+    {
+        let mut own = vec![];
+        let mut nested: BTreeMap<PathBuf, Vec<&Document>> = BTreeMap::new();
+
+        for doc in docs {
+            let mut components = doc
+                .path
+                .strip_prefix(prefix)
+                .unwrap_or(&doc.path)
+                .components();
+
+            match (components.next(), components.next()) {
+                (Some(name), Some(_)) => nested.entry(PathBuf::from(&name)).or_default().push(doc),
+                _ => own.push(doc.clone()),
+            }
+        }
+
+        Directory {
+            path: path.to_path_buf(),
+            docs: own,
+            dirs: nested
+                .into_iter()
+                .map(|(name, docs)| {
+                    Self::directory_from_docs(
+                        &path.join(&name),
+                        &prefix.join(&name),
+                        docs.into_iter(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Checks that no two documents in the directory are served from the
+    /// same URI, like `one.md` and `one/README.md`. Only one of them would
+    /// end up in the built site.
+    pub fn validate(&self, dir: &Directory) -> Result<(), NavigationError> {
+        self.validate_many(&[dir])
+    }
+
+    /// Like `validate`, but for the pages of several directories served
+    /// side by side, like the docs directory and the ones in
+    /// `extra_docs_dirs`. Two of the directories having a page or directory
+    /// with the same path at the top is a collision.
+    pub fn validate_many(&self, dirs: &[&Directory]) -> Result<(), NavigationError> {
+        let mut top_level: Vec<String> = vec![];
+        for dir in dirs {
+            let uris = self.top_level_uris(dir);
+
+            if let Some(uri) = uris
+                .iter()
+                .find(|uri| top_level.iter().any(|seen| same_uri(seen, uri)))
+            {
+                return Err(NavigationError::Collision { path: uri.clone() });
+            }
+
+            top_level.extend(uris);
+        }
+
+        let mut seen: BTreeMap<String, PathBuf> = BTreeMap::new();
+
+        for dir in dirs {
+            let root_name = self.root_name(dir);
+            let mut docs = vec![];
+            Self::collect_docs(dir, &mut docs);
+
+            for doc in docs {
+                let uri = self.uri_for(doc);
+                let source = root_name.join(&doc.path);
+
+                if let Some(first) = seen.get(&uri) {
+                    return Err(NavigationError::DuplicateUri {
+                        uri,
+                        first: first.clone(),
+                        second: source,
+                    });
+                }
+
+                seen.insert(uri, source);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The directory the paths of the documents under the root start from,
+    /// as written in the navigation rules: the docs directory, or another
+    /// one in the project, like `api`
+    fn root_name(&self, root: &Directory) -> PathBuf {
+        match root.path().strip_prefix(self.config.project_root()) {
+            Ok(relative) if !relative.starts_with(self.config.docs_dir_name()) => {
+                relative.to_path_buf()
+            }
+            _ => PathBuf::from(self.config.docs_dir_name()),
+        }
+    }
+
+    /// Builds the navigation tree of the language directory under the root,
+    /// e.g. `docs/de` for a navigation built `with_language("de")`.
+    pub fn build_for_language(&self, root: &Directory) -> Result<Vec<Link>, NavigationError> {
+        self.build_for(self.language_dir(root)?)
+    }
+
+    /// The directory under the root holding the pages of the language the
+    /// navigation is built for, or the root itself without a language.
+    pub fn language_dir<'d>(&self, root: &'d Directory) -> Result<&'d Directory, NavigationError> {
+        let language = match &self.language {
+            Some(language) => language,
+            None => return Ok(root),
+        };
+
+        root.dirs
             .iter()
-            .map(|d| Link {
-                title: d.title().to_owned(),
-                path: d.uri_path(),
-                children: vec![],
+            .find(|d| d.path().file_name() == Some(OsStr::new(language)))
+            .ok_or_else(|| NavigationError::UnknownLanguage {
+                language: language.clone(),
             })
-            .filter(|l| l.path != dir.index().uri_path())
-            .collect::<Vec<_>>();
+    }
 
-        let mut children = dir
-            .dirs
+    /// Merges several directories into one, for building a single generated
+    /// navigation out of their top level links. Two directories providing a
+    /// link to the same path is an error. The merged tree is built like the
+    /// tree of the first directory, whose index page and `.order` file decide
+    /// how the top level is sorted.
+    pub fn merge_dirs(&self, dirs: &[&Directory]) -> Result<Directory, NavigationError> {
+        self.validate_many(dirs)?;
+
+        Ok(Directory {
+            path: dirs.first().map(|d| d.path.clone()).unwrap_or_default(),
+            docs: dirs.iter().flat_map(|d| d.docs.iter().cloned()).collect(),
+            dirs: dirs.iter().flat_map(|d| d.dirs.iter().cloned()).collect(),
+        })
+    }
+
+    /// The URIs of the pages and directories at the top of the directory,
+    /// leaving out its own index
+    fn top_level_uris(&self, dir: &Directory) -> Vec<String> {
+        let pages = dir
+            .docs
             .iter()
-            .map(|d| Link {
-                title: d.index().title().to_owned(),
-                path: d.index().uri_path(),
-                children: d.into(),
+            .filter(|d| !d.is_index())
+            .map(|d| self.uri_for(d));
+        let dirs = dir.dirs.iter().filter_map(|d| {
+            Self::relative_dir(d)
+                .map(|p| self.strip_language(Link::path_to_uri(&p.join("index.html"), self.config)))
+        });
+
+        pages.chain(dirs).collect()
+    }
+
+    /// Builds one of the named navigation menus set in doctave.yaml, for
+    /// sites that split their navigation into several sections.
+    pub fn build_named(&self, name: &str, dir: &Directory) -> Result<Vec<Link>, NavigationError> {
+        let rules =
+            self.config
+                .named_navigation(name)
+                .ok_or_else(|| NavigationError::UnknownMenu {
+                    name: name.to_owned(),
+                    available: self
+                        .config
+                        .navigation_names()
+                        .iter()
+                        .map(|n| n.to_string())
+                        .collect(),
+                })?;
+
+        self.customize(rules, &self.default_links(dir))
+    }
+
+    /// The navigation tree for a directory when no rules have been set in
+    /// doctave.yaml.
+    fn default_links(&self, dir: &Directory) -> Vec<Link> {
+        let index_path = dir.index().map(|i| self.uri_for(i));
+
+        // The order files are listed in depends on the platform. Sorting by
+        // path first keeps ties and warnings the same on every machine.
+        let mut docs = dir.docs.iter().collect::<Vec<_>>();
+        docs.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut nested = dir.dirs.iter().collect::<Vec<_>>();
+        nested.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let pages = docs
+            .into_iter()
+            .filter(|d| !self.is_hidden(d))
+            .map(|d| (Self::file_name(&d.path), self.page_link(d)))
+            .filter(|(_, l)| Some(&l.path) != index_path.as_ref())
+            .collect::<Vec<_>>();
+
+        // Sibling directories don't depend on each other, so they can be
+        // built in parallel. Collecting keeps them in their original order.
+        // Each one collects its own warnings, which are added in the same
+        // order, whichever thread finishes first.
+        let dirs = nested
+            .par_iter()
+            .map(|d| {
+                let scoped = self.scoped();
+                let link = scoped
+                    .dir_link(d)
+                    .map(|link| (Self::file_name(&d.path), link));
+
+                (link, scoped.take_warnings())
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .filter_map(|(link, warnings)| {
+                self.warnings.lock().unwrap().extend(warnings);
+                link
             })
             .collect::<Vec<_>>();
 
-        links.append(&mut children);
-        links.sort_by(|a, b| alphanumeric_sort::compare_str(&a.title, &b.title));
+        // The index page can reverse the order of the directory's own
+        // children, or list the newest first. Nested directories are sorted
+        // by their own index.
+        let sort = dir.index().and_then(|i| self.index_sort(i));
+
+        if let Some(order) = dir.order() {
+            return self.order_by_file(dir, &order, pages.into_iter().chain(dirs).collect(), sort);
+        }
+
+        let mut links = pages.into_iter().map(|(_, link)| link).collect::<Vec<_>>();
+        let mut children = dirs.into_iter().map(|(_, link)| link).collect::<Vec<_>>();
+
+        match self.config.group_order() {
+            GroupOrder::Mixed => {
+                links.append(&mut children);
+                self.sort(&mut links, sort);
+            }
+            GroupOrder::FilesFirst => {
+                self.sort(&mut links, sort);
+                self.sort(&mut children, sort);
+                links.append(&mut children);
+            }
+            GroupOrder::DirsFirst => {
+                self.sort(&mut links, sort);
+                self.sort(&mut children, sort);
+                children.append(&mut links);
+                links = children;
+            }
+        }
 
         links
     }
-}
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
-pub struct Link {
-    pub path: String,
-    pub title: String,
-    pub children: Vec<Link>,
-}
+    /// The link to a nested directory in the generated navigation, with its
+    /// pages as children. Directories with nothing to show give `None`.
+    fn dir_link(&self, d: &Directory) -> Option<Link> {
+        let mut children = self.default_links(d);
 
-impl Link {
-    pub fn path_to_uri(path: &Path) -> String {
-        let mut tmp = path.to_owned();
+        // The index Doctave generates for a directory without a README.md
+        // only lists its pages, so the directory is linked like one without
+        // an index
+        let index = match d.index().filter(|i| !i.is_generated()) {
+            Some(index) => index,
+            None => return self.link_without_index(d, children),
+        };
 
-        // Default to stipping .html extensions
-        tmp.set_extension("");
+        // A draft index takes the rest of its section with it
+        if self.is_draft(index) {
+            return None;
+        }
 
-        if tmp.file_name() == Some(OsStr::new("index")) {
-            tmp = tmp
-                .parent()
-                .map(|p| p.to_owned())
-                .unwrap_or_else(|| PathBuf::from(""));
+        // A directory with a hidden index only stays in the
+        // navigation if there is something visible inside it.
+        if self.is_hidden(index) && children.is_empty() {
+            return None;
         }
 
-        // Need to force forward slashes here, since URIs will always
-        // work the same across all platforms.
-        let uri_path = tmp
-            .components()
-            .into_iter()
-            .map(|c| format!("{}", c.as_os_str().to_string_lossy()))
-            .collect::<Vec<_>>()
-            .join("/");
+        // A directory with a single page is replaced by the page
+        if self.config.collapse_single() && children.len() == 1 {
+            return children.pop();
+        }
 
-        format!("/{}", uri_path)
+        if self.config.index_as_child() && !self.is_hidden(index) {
+            children.insert(
+                0,
+                Link {
+                    title: self.config.index_child_title().to_owned(),
+                    path: self.uri_for(index),
+                    source_path: Some(self.source_path(index)),
+                    ..Link::default()
+                },
+            );
+        }
+
+        Some(Link {
+            title: self.title_for(index),
+            icon: index.icon().map(|i| i.to_owned()),
+            description: index.description().map(|d| d.to_owned()),
+            badges: index.badges(),
+            path: self.uri_for(index),
+            source_path: Some(self.source_path(index)),
+            order: index.order(),
+            pin: index.pin(),
+            date: index.date(),
+            children,
+            ..Link::default()
+        })
     }
 
-    pub fn path_to_uri_with_extension(path: &Path) -> String {
-        let mut tmp = path.to_owned();
+    /// The ordering of a directory's links given by its `.order` file. The
+    /// listed names come first, and the links the file doesn't mention
+    /// follow in their usual sort order. Names that aren't in the directory
+    /// are reported as warnings.
+    fn order_by_file(
+        &self,
+        dir: &Directory,
+        order: &[String],
+        mut named: Vec<(String, Link)>,
+        sort: Option<SortMode>,
+    ) -> Vec<Link> {
+        let mut links = vec![];
 
-        if tmp.file_name() == Some(OsStr::new("index")) {
-            tmp = tmp
-                .parent()
-                .map(|p| p.to_owned())
-                .unwrap_or_else(|| PathBuf::from(""));
+        for name in order {
+            if let Some(i) = named.iter().position(|(n, _)| n == name) {
+                links.push(named.remove(i).1);
+            } else if !dir.contains(name) {
+                self.warn(format!(
+                    "The .order file in {} lists {}, which is not in the directory. \
+                     It will be skipped.",
+                    dir.path()
+                        .strip_prefix(self.config.project_root())
+                        .unwrap_or_else(|_| dir.path())
+                        .display(),
+                    name
+                ));
+            }
         }
 
-        // Need to force forward slashes here, since URIs will always
-        // work the same across all platforms.
-        let uri_path = tmp
-            .components()
-            .into_iter()
-            .map(|c| format!("{}", c.as_os_str().to_string_lossy()))
-            .collect::<Vec<_>>()
-            .join("/");
+        let mut rest = named.into_iter().map(|(_, link)| link).collect::<Vec<_>>();
+        self.sort(&mut rest, sort);
+        links.append(&mut rest);
+
+        links
+    }
 
-        format!("/{}", uri_path)
+    fn file_name(path: &Path) -> String {
+        path.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use std::collections::BTreeMap;
-    use std::path::Path;
+    /// The link to a single page in the generated navigation
+    fn page_link(&self, doc: &Document) -> Link {
+        Link {
+            title: self.title_for(doc),
+            icon: doc.icon().map(|i| i.to_owned()),
+            description: doc.description().map(|d| d.to_owned()),
+            badges: doc.badges(),
+            path: self.uri_for(doc),
+            unslugged_uri: Some(self.strip_language(doc.source_uri_path(self.config)))
+                .filter(|uri| uri != &self.uri_for(doc)),
+            source_path: Some(self.source_path(doc)),
+            order: doc.order(),
+            pin: doc.pin(),
+            date: doc.date(),
+            ..Link::default()
+        }
+    }
 
-    use crate::Document;
+    /// The file the document is built from, relative to the project root
+    fn source_path(&self, doc: &Document) -> PathBuf {
+        Path::new(self.config.docs_dir_name()).join(&doc.path)
+    }
 
-    fn page(path: &str, name: &str) -> Document {
-        let mut frontmatter = BTreeMap::new();
-        frontmatter.insert("title".to_string(), name.to_string());
+    /// The generated navigation grouped by the `category` set in the
+    /// frontmatter of each page. Groups are sorted by name, with the pages
+    /// without a category in an "Uncategorized" group at the end.
+    fn category_links(&self, dir: &Directory) -> Vec<Link> {
+        let mut categories: BTreeMap<String, Vec<Link>> = BTreeMap::new();
+        let mut uncategorized = vec![];
 
-        Document::new(Path::new(path), "Not important".to_string(), frontmatter)
+        let mut docs = vec![];
+        Self::collect_docs(dir, &mut docs);
+
+        for doc in docs {
+            let link = self.page_link(doc);
+
+            if link.path == Link::root_uri(self.config) || self.is_hidden(doc) {
+                continue;
+            }
+
+            match doc.category() {
+                Some(category) => categories
+                    .entry(category.to_owned())
+                    .or_default()
+                    .push(link),
+                None => uncategorized.push(link),
+            }
+        }
+
+        let mut groups = categories.into_iter().collect::<Vec<_>>();
+        groups.sort_by(|a, b| alphanumeric_sort::compare_str(&a.0, &b.0));
+        if !uncategorized.is_empty() {
+            groups.push((String::from("Uncategorized"), uncategorized));
+        }
+
+        groups
+            .into_iter()
+            .map(|(title, mut children)| {
+                self.sort(&mut children, None);
+
+                Link {
+                    path: String::new(),
+                    title,
+                    children,
+                    ..Link::default()
+                }
+            })
+            .collect()
     }
 
-    fn config(yaml: Option<&str>) -> Config {
-        let conf = yaml.unwrap_or("---\ntitle: My project\n");
+    fn collect_docs<'d>(dir: &'d Directory, docs: &mut Vec<&'d Document>) {
+        docs.extend(dir.docs.iter());
 
-        Config::from_yaml_str(&Path::new("project"), conf).unwrap()
+        for child in &dir.dirs {
+            Self::collect_docs(child, docs);
+        }
     }
 
-    #[test]
-    fn basic() {
-        let config = config(None);
-        let root = Directory {
-            path: PathBuf::from("docs"),
-            docs: vec![
-                page("README.md", "Getting Started"),
-                page("one.md", "One"),
-                page("two.md", "Two"),
-            ],
-            dirs: vec![Directory {
-                path: PathBuf::from("docs").join("child"),
-                docs: vec![
-                    page("child/README.md", "Nested Root"),
-                    page("child/three.md", "Three"),
-                ],
-                dirs: vec![],
-            }],
+    /// Sorts a level of the generated navigation, using the sort set in the
+    /// index page of the directory if there is one
+    fn sort(&self, links: &mut [Link], sort: Option<SortMode>) {
+        let mode = match sort {
+            Some(mode) => mode,
+            None if self.config.sort_descending() => SortMode::AlphanumericDesc,
+            None => SortMode::Alphanumeric,
         };
 
-        let navigation = Navigation::new(&config);
+        sort_links(links, mode);
+    }
 
-        assert_eq!(
-            navigation.build_for(&root),
-            vec![
-                Link {
-                    path: String::from("/child"),
-                    title: String::from("Nested Root"),
-                    children: vec![Link {
-                        path: String::from("/child/three"),
-                        title: String::from("Three"),
-                        children: vec![]
-                    }]
-                },
-                Link {
-                    path: String::from("/one"),
-                    title: String::from("One"),
-                    children: vec![]
-                },
-                Link {
-                    path: String::from("/two"),
-                    title: String::from("Two"),
-                    children: vec![]
-                },
-            ]
-        )
+    /// The sort set in the frontmatter of an index page. Unknown sorts are
+    /// reported, and the directory is sorted as usual.
+    fn index_sort(&self, index: &Document) -> Option<SortMode> {
+        let name = index.sort()?;
+        let mode = SortMode::from_name(name);
+
+        if mode.is_none() {
+            self.warn(format!(
+                "{} sets an unknown sort \"{}\". Expected one of {}. \
+                 Its directory will be sorted as usual.",
+                Path::new(self.config.docs_dir_name())
+                    .join(&index.path)
+                    .display(),
+                name,
+                SortMode::NAMES.join(", ")
+            ));
+        }
+
+        mode
     }
 
-    #[test]
-    fn sorting_alphanumerically() {
-        let config = config(None);
-        let root = Directory {
-            path: PathBuf::from("docs"),
-            docs: vec![
-                page("README.md", "Getting Started"),
-                page("001.md", "bb"),
-                page("002.md", "11"),
-            ],
-            dirs: vec![
-                Directory {
-                    path: PathBuf::from("docs").join("bb_child"),
-                    docs: vec![
-                        page("child/README.md", "Index"),
-                        page("child/001.md", "BB"),
-                        page("child/002.md", "22"),
-                        page("child/003.md", "AA"),
-                        page("child/004.md", "11"),
-                    ],
-                    dirs: vec![],
+    /// The title of the document in the generated navigation. A `nav_title`
+    /// set in the frontmatter is used as is.
+    fn title_for(&self, doc: &Document) -> String {
+        if let Some(nav_title) = doc.nav_title() {
+            nav_title.to_owned()
+        } else if self.config.title_case() {
+            Link::title_case(&Link::strip_markdown(doc.title()))
+        } else {
+            Link::strip_markdown(doc.title())
+        }
+    }
+
+    /// Whether the document should be left out of the generated navigation,
+    /// either through its frontmatter, a `nav_exclude` pattern, or by being
+    /// a draft. Patterns are matched against the path of the file inside the
+    /// docs directory.
+    fn is_hidden(&self, doc: &Document) -> bool {
+        if doc.hidden() || self.is_draft(doc) {
+            return true;
+        }
+
+        let path = doc.path.to_string_lossy().replace('\\', "/");
+
+        self.config
+            .nav_exclude()
+            .iter()
+            .any(|pattern| glob_matches(pattern, &path))
+    }
+
+    fn is_draft(&self, doc: &Document) -> bool {
+        !self.include_drafts && doc.is_draft()
+    }
+
+    /// The link for a directory without a README.md to take its title
+    /// from. The title is made up from the directory name, and the link
+    /// points to the first page inside the directory. Directories without
+    /// any pages are left out.
+    fn link_without_index(&self, dir: &Directory, children: Vec<Link>) -> Option<Link> {
+        let first = children.first()?;
+        let (path, source_path) = (first.path.clone(), first.source_path.clone());
+
+        let name = dir
+            .path()
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let title = Link::title_case(&name);
+
+        self.warn(format!(
+            "The directory {} does not have a README.md. \
+             Using \"{}\" as its title in the navigation.",
+            dir.path()
+                .strip_prefix(self.config.project_root())
+                .unwrap_or_else(|_| dir.path())
+                .display(),
+            title
+        ));
+
+        // The directory itself has no page, so navigation rules can only
+        // find it by the URI its index would have had.
+        let unslugged_uri = Self::relative_dir(dir)
+            .map(|p| self.strip_language(Link::path_to_uri(&p.join("index.html"), self.config)));
+
+        Some(Link {
+            title,
+            path,
+            unslugged_uri,
+            source_path,
+            children,
+            ..Link::default()
+        })
+    }
+
+    /// The path of the directory inside the docs folder, taken from the
+    /// pages in it
+    fn relative_dir(dir: &Directory) -> Option<PathBuf> {
+        match dir.docs.first() {
+            Some(doc) => doc.path.parent().map(|p| p.to_owned()),
+            None => dir
+                .dirs
+                .iter()
+                .find_map(Self::relative_dir)
+                .and_then(|p| p.parent().map(|p| p.to_owned())),
+        }
+    }
+
+    /// Drops any links nested deeper than the given depth, counting from
+    /// the top level of the navigation.
+    fn limit_depth(links: &mut [Link], depth: usize) {
+        for link in links {
+            if depth <= 1 {
+                link.children.truncate(0);
+            } else {
+                Self::limit_depth(&mut link.children, depth - 1);
+            }
+        }
+    }
+
+    /// Builds the navigation for the directory, with the parts only needed
+    /// for rendering filled in, and serializes it into JSON. Errors in the
+    /// navigation are reported as serialization errors.
+    pub fn to_json(&self, dir: &Directory) -> Result<String, serde_json::Error> {
+        let mut links = self.build_for(dir).map_err(serde::ser::Error::custom)?;
+        self.annotate(&mut links);
+
+        self.links_to_json(&links)
+    }
+
+    /// Serializes the navigation tree into JSON, wrapped in a
+    /// `NavigationExport` with the version of the format. Children are kept
+    /// nested under their parent link.
+    pub fn links_to_json(&self, links: &[Link]) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&NavigationExport::new(links))
+    }
+
+    /// Writes the navigation tree as a nested Markdown list, like a
+    /// `SUMMARY.md` table of contents. Each level is indented by two more
+    /// spaces. Group headings are written in bold, and dividers are left
+    /// out.
+    pub fn to_markdown(&self, links: &[Link]) -> String {
+        let mut markdown = String::new();
+
+        self.walk(links, |link, depth| {
+            if link.divider {
+                return;
+            }
+
+            markdown.push_str(&"  ".repeat(depth));
+
+            if link.is_group() {
+                markdown.push_str(&format!("- **{}**\n", link.title));
+            } else {
+                markdown.push_str(&format!("- [{}]({})\n", link.title, link.path));
+            }
+        });
+
+        markdown
+    }
+
+    /// Flattens the navigation tree into the order a reader would go
+    /// through the pages: each link is followed by its own children,
+    /// depth-first. The returned links don't include their children.
+    /// Group headings are not pages, so only their children are included.
+    pub fn flatten(&self, links: &[Link]) -> Vec<Link> {
+        let mut flat = vec![];
+
+        for link in links {
+            if !link.is_group() && !link.divider {
+                flat.push(link.without_children());
+            }
+            flat.append(&mut self.flatten(&link.children));
+        }
+
+        flat
+    }
+
+    /// Lists the path of every page in the navigation tree, depth-first,
+    /// each only once. External links, links to headings and group
+    /// headings are left out.
+    /// Meant for listing the pages of the site, e.g. in a sitemap.
+    pub fn all_paths(&self, links: &[Link]) -> Vec<String> {
+        let mut paths: Vec<String> = vec![];
+
+        for link in self.flatten(links) {
+            if !link.is_external() && !link.is_anchor() && !paths.contains(&link.path) {
+                paths.push(link.path);
+            }
+        }
+
+        paths
+    }
+
+    /// Lists the files of all pages in the order they appear in the
+    /// navigation, each only once, e.g. for putting the whole site on a
+    /// single page. External links, links to headings and group headings
+    /// are left out.
+    pub fn reading_order(&self, dir: &Directory) -> Result<Vec<PathBuf>, NavigationError> {
+        let links = self.build_for(dir)?;
+
+        Ok(self.sources_in_order(&links))
+    }
+
+    fn sources_in_order(&self, links: &[Link]) -> Vec<PathBuf> {
+        let mut paths = vec![];
+        for link in self.flatten(links) {
+            if link.is_external() || link.is_anchor() {
+                continue;
+            }
+
+            if let Some(path) = link.source_path {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+
+        paths
+    }
+
+    /// Counts the pages reachable through the navigation, e.g. for showing
+    /// "Page 3 of 12". Counts the same pages as `all_paths`.
+    pub fn page_count(&self, links: &[Link]) -> usize {
+        self.all_paths(links).len()
+    }
+
+    /// Lists the documents under the directory that don't appear anywhere
+    /// in the navigation, as paths starting with the docs directory like in
+    /// the navigation rules, or with the directory in the project holding
+    /// them. The root index page, hidden pages and the index pages Doctave
+    /// generates are not expected to be linked, so they are never reported.
+    pub fn unreferenced(&self, dir: &Directory, links: &[Link]) -> Vec<PathBuf> {
+        let referenced = self.all_paths(links);
+        let mut missing = vec![];
+
+        self.collect_unreferenced(dir, &self.root_name(dir), &referenced, &mut missing);
+
+        missing
+    }
+
+    fn collect_unreferenced(
+        &self,
+        dir: &Directory,
+        root_name: &Path,
+        referenced: &[String],
+        missing: &mut Vec<PathBuf>,
+    ) {
+        for doc in &dir.docs {
+            let uri = self.uri_for(doc);
+
+            if uri == Link::root_uri(self.config) || self.is_hidden(doc) || doc.is_generated() {
+                continue;
+            }
+
+            if !referenced.iter().any(|path| same_uri(path, &uri)) {
+                missing.push(root_name.join(&doc.path));
+            }
+        }
+
+        for child in &dir.dirs {
+            self.collect_unreferenced(child, root_name, referenced, missing);
+        }
+    }
+
+    /// Finds the previous and next pages for the page with the given path,
+    /// in reading order. External links are skipped.
+    pub fn neighbors(&self, links: &[Link], current: &str) -> (Option<Link>, Option<Link>) {
+        let mut pages = self
+            .flatten(links)
+            .into_iter()
+            .filter(|l| !l.is_external())
+            .collect::<Vec<_>>();
+
+        // A directory followed by its own index as the first child should
+        // not link to itself.
+        pages.dedup_by(|a, b| a.path == b.path);
+
+        match pages.iter().position(|l| l.path == current) {
+            Some(i) => (
+                i.checked_sub(1).map(|prev| pages[prev].clone()),
+                pages.get(i + 1).cloned(),
+            ),
+            None => (None, None),
+        }
+    }
+
+    /// Finds the trail of links leading from the top of the navigation down
+    /// to the page with the given path, ending with the page itself. Group
+    /// headings are left out. Returns an empty list if the page is not in
+    /// the navigation.
+    pub fn breadcrumbs(&self, links: &[Link], current: &str) -> Vec<Link> {
+        match self.find(links, current) {
+            Some(page) => self
+                .ancestors(links, current)
+                .into_iter()
+                .chain(std::iter::once(page))
+                .map(Link::without_children)
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// The links the page with the given path is nested under, starting
+    /// from the top of the navigation. Like the breadcrumbs without the page
+    /// itself, but borrowed from the tree. Returns an empty list for pages
+    /// on the top level, or not in the navigation.
+    pub fn ancestors<'l>(&self, links: &'l [Link], path: &str) -> Vec<&'l Link> {
+        Self::trail_to(links, path.trim_end_matches('/')).unwrap_or_default()
+    }
+
+    fn trail_to<'l>(links: &'l [Link], path: &str) -> Option<Vec<&'l Link>> {
+        for link in links {
+            if !link.is_group() && !link.divider && link.path.trim_end_matches('/') == path {
+                return Some(vec![]);
+            }
+
+            if let Some(mut trail) = Self::trail_to(&link.children, path) {
+                if !link.is_group() {
+                    trail.insert(0, link);
+                }
+                return Some(trail);
+            }
+        }
+
+        None
+    }
+
+    /// Resolves the paths of related pages, as given in the `related`
+    /// frontmatter key, to links without their children. Paths are matched
+    /// the same way as in the navigation rules. Paths that don't match any
+    /// page are left out with a warning.
+    pub fn related(&self, links: &[Link], paths: &[PathBuf]) -> Vec<Link> {
+        let mut related = vec![];
+
+        for path in paths {
+            match self.find_matching_link(path, links) {
+                Ok(link) => related.push(link.without_children()),
+                Err(_) => self.warn(format!(
+                    "Could not find the related page {}. It will be left out.",
+                    path.display()
+                )),
+            }
+        }
+
+        related
+    }
+
+    /// Sets the depth of every link in the tree, so that templates can
+    /// indent links without keeping track of how deep they have recursed.
+    pub fn annotate_depth(&self, links: &mut [Link]) {
+        self.walk_mut(links, |link, depth| link.depth = depth);
+    }
+
+    /// Sets the DOM id of every link in the tree, as given by `Link::dom_id`,
+    /// so that scripts can find a link in the rendered navigation. Links
+    /// sharing an id, like a page listed twice or a directory and its
+    /// overview, get a counter after it, starting with `---2` for the
+    /// second one. `dom_id` never gives three hyphens in a row, so ids
+    /// with a counter can't be the id of another link.
+    pub fn annotate_ids(&self, links: &mut [Link]) {
+        let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+
+        self.walk_mut(links, |link, _| {
+            let id = link.dom_id();
+            let count = seen.entry(id.clone()).or_insert(0);
+            *count += 1;
+
+            link.id = if *count == 1 {
+                id
+            } else {
+                format!("{}---{}", id, count)
+            };
+        });
+    }
+
+    /// Fills in the parts of the links that are only needed for rendering
+    pub fn annotate(&self, links: &mut [Link]) {
+        self.annotate_depth(links);
+        self.annotate_ids(links);
+        self.truncate_titles(links);
+    }
+
+    /// Shortens titles longer than the `nav_title_max_len` setting, if it is
+    /// set. The full title is kept in `title_full`.
+    pub fn truncate_titles(&self, links: &mut [Link]) {
+        if let Some(max) = self.config.nav_title_max_len() {
+            self.walk_mut(links, |link, _| link.truncate_title(max));
+        }
+    }
+
+    /// Calls `visit` for every link in the tree with its depth, starting at
+    /// 0 for the top level. Links are visited depth-first, each before its
+    /// own children, which is the order a reader would go through them.
+    pub fn walk<F: FnMut(&Link, usize)>(&self, links: &[Link], mut visit: F) {
+        fn walk_level<F: FnMut(&Link, usize)>(links: &[Link], depth: usize, visit: &mut F) {
+            for link in links {
+                visit(link, depth);
+                walk_level(&link.children, depth + 1, visit);
+            }
+        }
+
+        walk_level(links, 0, &mut visit);
+    }
+
+    /// Like `walk`, but lets `visit` change the links in place. Changes to
+    /// a link's children are seen when its children are visited next.
+    pub fn walk_mut<F: FnMut(&mut Link, usize)>(&self, links: &mut [Link], mut visit: F) {
+        fn walk_level<F: FnMut(&mut Link, usize)>(links: &mut [Link], depth: usize, visit: &mut F) {
+            for link in links {
+                visit(link, depth);
+                walk_level(&mut link.children, depth + 1, visit);
+            }
+        }
+
+        walk_level(links, 0, &mut visit);
+    }
+
+    /// Marks the link pointing to the current page as active, and all the
+    /// links it is nested under as part of the active trail. Nothing is
+    /// marked if the page is not in the navigation.
+    ///
+    /// The links on the trail are also expanded, up to `max_expand_depth`
+    /// levels above the page. Sections further up keep their own setting,
+    /// so that deep trees don't open all the way down. `None` expands the
+    /// whole trail.
+    pub fn mark_active(
+        &self,
+        links: &mut [Link],
+        current_path: &str,
+        max_expand_depth: Option<usize>,
+    ) {
+        Self::mark_trail(links, current_path, max_expand_depth);
+    }
+
+    /// Returns how many levels above the current page the links are, if the
+    /// page is among them
+    fn mark_trail(links: &mut [Link], current_path: &str, max: Option<usize>) -> Option<usize> {
+        for link in links {
+            if link.path == current_path {
+                link.active = true;
+                return Some(0);
+            }
+
+            if let Some(below) = Self::mark_trail(&mut link.children, current_path, max) {
+                let distance = below + 1;
+
+                link.active_trail = true;
+                if max.map_or(true, |max| distance <= max) {
+                    link.expanded = true;
+                }
+                return Some(distance);
+            }
+        }
+
+        None
+    }
+
+    /// Finds the link with the given path anywhere in the navigation,
+    /// searching depth first. Trailing slashes are ignored, so that
+    /// directories can be looked up regardless of the `trailing_slash`
+    /// setting. Group headings have no path and never match.
+    pub fn find<'l>(&self, links: &'l [Link], path: &str) -> Option<&'l Link> {
+        let path = path.trim_end_matches('/');
+
+        for link in links {
+            if !link.is_group() && !link.divider && link.path.trim_end_matches('/') == path {
+                return Some(link);
+            }
+
+            if let Some(found) = self.find(&link.children, path) {
+                return Some(found);
+            }
+        }
+
+        None
+    }
+
+    /// Finds the links nested under the link with the given path, e.g. for
+    /// showing only the pages of one section. A page without children gives
+    /// an empty list.
+    pub fn subtree<'l>(&self, links: &'l [Link], root_path: &str) -> Option<&'l [Link]> {
+        self.find(links, root_path).map(|link| &link.children[..])
+    }
+
+    /// Compares two navigation trees by the paths of their links, e.g. to
+    /// show how a change to the docs affects the sidebar. A link is moved
+    /// when its path is nested under a different parent in the new tree.
+    /// Group headings and dividers have no path, so only the links inside
+    /// groups are compared.
+    pub fn diff(&self, old: &[Link], new: &[Link]) -> NavDiff {
+        let old = Self::links_by_path(old);
+        let new = Self::links_by_path(new);
+        let mut diff = NavDiff::default();
+
+        for (path, (link, parent)) in &new {
+            match old.get(path) {
+                None => {
+                    diff.added.insert(path.clone(), link.clone());
+                }
+                Some((_, old_parent)) if old_parent != parent => {
+                    diff.moved.insert(
+                        path.clone(),
+                        MovedLink {
+                            link: link.clone(),
+                            from: old_parent.clone(),
+                            to: parent.clone(),
+                        },
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+
+        for (path, (link, _)) in old {
+            if !new.contains_key(&path) {
+                diff.removed.insert(path, link);
+            }
+        }
+
+        diff
+    }
+
+    /// Every link in the tree by its path, without children, along with the
+    /// path of the link it is nested under. The first link to a path wins.
+    fn links_by_path(links: &[Link]) -> BTreeMap<String, (Link, Option<String>)> {
+        fn collect(
+            links: &[Link],
+            parent: Option<&str>,
+            found: &mut BTreeMap<String, (Link, Option<String>)>,
+        ) {
+            for link in links {
+                if link.is_group() || link.divider {
+                    collect(&link.children, parent, found);
+                    continue;
+                }
+
+                found
+                    .entry(link.path.clone())
+                    .or_insert_with(|| (link.without_children(), parent.map(|p| p.to_owned())));
+                collect(&link.children, Some(&link.path), found);
+            }
+        }
+
+        let mut found = BTreeMap::new();
+        collect(links, None, &mut found);
+        found
+    }
+
+    /// The first page nested under the link that has no children of its
+    /// own, searching depth-first. External links, group headings and
+    /// dividers are skipped. Useful for sections whose index is only a
+    /// stub, to link to their first page instead.
+    pub fn first_leaf<'l>(&self, link: &'l Link) -> Option<&'l Link> {
+        link.children.iter().find_map(|child| {
+            if child.is_section() {
+                self.first_leaf(child)
+            } else {
+                Some(child).filter(|c| c.is_leaf() && !c.is_external())
+            }
+        })
+    }
+
+    /// Finds the link the page with the given path is nested directly
+    /// under. Pages on the top level of the navigation have no parent.
+    pub fn parent_of<'l>(&self, links: &'l [Link], path: &str) -> Option<&'l Link> {
+        let path = path.trim_end_matches('/');
+
+        for link in links {
+            let is_child = link.children.iter().any(|child| {
+                !child.is_group() && !child.divider && child.path.trim_end_matches('/') == path
+            });
+
+            if is_child {
+                return Some(link);
+            }
+
+            if let Some(parent) = self.parent_of(&link.children, path) {
+                return Some(parent);
+            }
+        }
+
+        None
+    }
+
+    /// Finds the other links on the same level as the page with the given
+    /// path, e.g. for listing the rest of the section. Dividers are left
+    /// out. Returns an empty list if the page is not in the navigation.
+    pub fn siblings<'l>(&self, links: &'l [Link], path: &str) -> Vec<&'l Link> {
+        let path = path.trim_end_matches('/');
+        let is_page =
+            |l: &Link| !l.is_group() && !l.divider && l.path.trim_end_matches('/') == path;
+
+        match Self::level_of(links, &is_page) {
+            Some(level) => level.iter().filter(|l| !l.divider && !is_page(l)).collect(),
+            None => vec![],
+        }
+    }
+
+    fn level_of<'l>(links: &'l [Link], is_page: &dyn Fn(&Link) -> bool) -> Option<&'l [Link]> {
+        if links.iter().any(is_page) {
+            return Some(links);
+        }
+
+        links
+            .iter()
+            .find_map(|l| Self::level_of(&l.children, is_page))
+    }
+
+    /// Customizes the navigation tree given some rules provided through the
+    /// doctave.yaml config.
+    ///
+    /// Note that the config validates that any files/directories referenced
+    /// in the rules already exist, but the files could still have been
+    /// removed between the validation and building these rules, or the path
+    /// may not point at a Markdown page. In that case we return an error
+    /// describing the rule that could not be matched.
+    ///
+    /// Note that in the case where an explicit path is provided, the link is
+    /// not necessarily a direct child of its parent. It could be that links
+    /// under a directory actually point to a parent's sibling, or to somewhere
+    /// else in the tree.
+    ///
+    /// Links are listed in the order of the rules, regardless of how the
+    /// default links were sorted. Only links included through a wildcard or
+    /// a glob keep a sorted order.
+    fn customize(&self, rules: &[NavRule], default: &[Link]) -> Result<Vec<Link>, NavigationError> {
+        self.customize_nested(rules, default, default, &mut vec![])
+    }
+
+    /// Customizes a level of the navigation. The directories being
+    /// customized above this level are kept track of, so that a directory
+    /// nested under itself can be reported instead of repeated.
+    ///
+    /// The level holds the generated links the rules replace, which an
+    /// `Auto` rule fills in from.
+    fn customize_nested(
+        &self,
+        rules: &[NavRule],
+        default: &[Link],
+        level: &[Link],
+        parents: &mut Vec<PathBuf>,
+    ) -> Result<Vec<Link>, NavigationError> {
+        let mut links = vec![];
+        let mut auto_position = None;
+
+        for rule in rules {
+            let rule = match self.active_rule(rule) {
+                Some(rule) => rule,
+                None => continue,
+            };
+
+            match rule {
+                // A link to a heading inside the page. Headings don't have
+                // children of their own.
+                NavRule::File(path, options) if options.anchor.is_some() => {
+                    let anchor = options.anchor.as_deref().unwrap_or_default();
+                    let page = if self.is_index_file(path) {
+                        path.parent().unwrap_or(path)
+                    } else {
+                        path
+                    };
+
+                    let mut link = self.find_matching_link(page, default)?.without_children();
+                    link.apply(options);
+                    link.path = format!("{}#{}", link.path, Link::slugify(anchor));
+
+                    self.push_unique(&mut links, link, path);
+                }
+                // Pointing at the index of a directory means the whole
+                // directory, with all of its pages.
+                NavRule::File(path, options) if self.is_index_file(path) => {
+                    let dir = path.parent().unwrap_or(path);
+                    let options = DirOptions {
+                        link: options.clone(),
+                        ..DirOptions::default()
+                    };
+                    let index_link = self.customize_dir(
+                        dir,
+                        &Some(DirIncludeRule::WildCard),
+                        &options,
+                        default,
+                        parents,
+                    )?;
+
+                    self.push_unique(&mut links, index_link, path);
+                }
+                NavRule::File(path, options) => {
+                    let mut link = self.find_matching_link(path, default)?;
+                    link.apply(options);
+
+                    self.push_unique(&mut links, link, path);
+                }
+                NavRule::Dir(path, dir_rule, options) => {
+                    let index_link =
+                        self.customize_dir(path, dir_rule, options, default, parents)?;
+
+                    self.push_unique(&mut links, index_link, path);
+                }
+                NavRule::Link {
+                    title,
+                    url,
+                    target,
+                    classes,
+                } => {
+                    let mut link = Link {
+                        path: url.clone(),
+                        title: title.clone(),
+                        classes: classes.clone(),
+                        ..Link::default()
+                    };
+
+                    // Links leaving the site open in a new tab, unless the
+                    // rule says otherwise
+                    link.target = target.clone().or_else(|| {
+                        if link.is_external() {
+                            Some(String::from("_blank"))
+                        } else {
+                            None
+                        }
+                    });
+
+                    links.push(link);
+                }
+                NavRule::Divider => links.push(Link {
+                    divider: true,
+                    ..Link::default()
+                }),
+                NavRule::Auto => {
+                    auto_position.get_or_insert(links.len());
+                }
+                NavRule::Group { title, children } => links.push(Link {
+                    path: String::new(),
+                    title: title.clone(),
+                    children: self.customize_nested(children, default, level, parents)?,
+                    ..Link::default()
+                }),
+                NavRule::Generated { title, from } => links.push(Link {
+                    path: String::new(),
+                    title: title.clone(),
+                    children: self.generated_children(from, default),
+                    ..Link::default()
+                }),
+                // Already unwrapped by `active_rule`
+                NavRule::Only { .. } => {}
+                NavRule::Glob(pattern) => {
+                    let mut matches = vec![];
+                    Self::collect_glob_matches(&self.rule_uri(pattern), default, &mut matches);
+                    matches.sort_by(|a, b| alphanumeric_sort::compare_str(&a.title, &b.title));
+
+                    for link in matches {
+                        // Links explicitly listed before the glob take precedence
+                        if !links.iter().any(|l: &Link| same_uri(&l.path, &link.path)) {
+                            links.push(link);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Only once all the other rules are known can the rest of the
+        // generated links be listed, since rules after the `Auto` count too
+        if let Some(position) = auto_position {
+            let referenced = self.all_paths(&links);
+            let rest = level
+                .iter()
+                .filter(|l| !referenced.iter().any(|path| same_uri(path, &l.path)))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            links.splice(position..position, rest);
+        }
+
+        Ok(links)
+    }
+
+    /// Every page nested anywhere under the directory, without children,
+    /// sorted by title. Index pages of nested directories are included,
+    /// but not the index of the directory itself.
+    fn generated_children(&self, dir: &Path, links: &[Link]) -> Vec<Link> {
+        let prefix = format!("{}/", self.rule_uri(dir).trim_end_matches('/'));
+
+        let mut pages = vec![];
+        Self::collect_generated(&prefix, links, &mut pages);
+        pages.sort_by(|a, b| alphanumeric_sort::compare_str(&a.title, &b.title));
+
+        pages
+    }
+
+    fn collect_generated(prefix: &str, links: &[Link], pages: &mut Vec<Link>) {
+        for link in links {
+            // Directories without an index borrow the path of their first
+            // page, which is already listed on its own.
+            let borrowed = link.children.first().map_or(false, |c| c.path == link.path);
+
+            if link.path.starts_with(prefix) && !link.is_anchor() && !borrowed {
+                pages.push(link.without_children());
+            }
+            Self::collect_generated(prefix, &link.children, pages);
+        }
+    }
+
+    /// The rule to apply for the current profile. Rules limited to other
+    /// profiles give `None`, as do all limited rules when no profile is set.
+    fn active_rule<'r>(&self, rule: &'r NavRule) -> Option<&'r NavRule> {
+        match rule {
+            NavRule::Only { profiles, rule } => match &self.profile {
+                Some(profile) if profiles.contains(profile) => self.active_rule(rule),
+                _ => None,
+            },
+            rule => Some(rule),
+        }
+    }
+
+    /// Builds the link for a directory rule, with the children the rule
+    /// asks for.
+    fn customize_dir(
+        &self,
+        path: &Path,
+        dir_rule: &Option<DirIncludeRule>,
+        options: &DirOptions,
+        default: &[Link],
+        parents: &mut Vec<PathBuf>,
+    ) -> Result<Link, NavigationError> {
+        let uri = self.rule_uri(path);
+        if let Some(start) = parents
+            .iter()
+            .position(|p| same_uri(&self.rule_uri(p), &uri))
+        {
+            let mut chain = parents[start..].to_vec();
+            chain.push(path.to_path_buf());
+
+            return Err(NavigationError::Cycle { chain });
+        }
+
+        let mut index_link = self.find_matching_link(path, default)?;
+        index_link.apply(&options.link);
+        index_link.expanded = !options.collapsed;
+
+        // A directory without an index page, given a title in the rules, is
+        // shown as a heading unless it should link to its first page
+        let has_index = index_link.matches_path(&uri);
+        if !has_index && options.link.title.is_some() && !options.link_to_first {
+            index_link.path = String::new();
+            index_link.source_path = None;
+        }
+
+        match dir_rule {
+            // Don't include any children
+            None => index_link.children.truncate(0),
+            // Include all children
+            Some(DirIncludeRule::WildCard) => {}
+            // Include all nested pages, flattened into one level
+            Some(DirIncludeRule::DeepWildCard) => {
+                let mut pages = vec![];
+                Self::collect_pages(&index_link.children, &mut pages);
+                pages.sort_by(|a, b| alphanumeric_sort::compare_str(&a.title, &b.title));
+                pages.dedup_by(|a, b| a.path == b.path);
+
+                index_link.children = pages;
+            }
+            // Include all children, except for the listed ones
+            Some(DirIncludeRule::Except(paths)) => {
+                let excluded = paths.iter().map(|p| self.rule_uri(p)).collect::<Vec<_>>();
+
+                index_link.children.retain(|link| {
+                    !excluded
+                        .iter()
+                        .any(|uri| link.matches_path(uri) || link.matches_unslugged_uri(uri))
+                });
+            }
+            // Include only links that match the description
+            Some(DirIncludeRule::Explicit(nested_rules)) => {
+                parents.push(path.to_path_buf());
+                let children =
+                    self.customize_nested(nested_rules, default, &index_link.children, parents);
+                parents.pop();
+
+                index_link.children = children?;
+            }
+        }
+
+        // Pages included automatically can be sorted differently from the
+        // rest of the navigation
+        if let (Some(mode), Some(rule)) = (options.sort, dir_rule) {
+            if !matches!(rule, DirIncludeRule::Explicit(_)) {
+                sort_links(&mut index_link.children, mode);
+            }
+        }
+
+        // The index page may only be a stub, so the directory can link to
+        // its first page instead. An overview still links to the index.
+        let index_path = index_link.path.clone();
+        let index_source = index_link.source_path.clone();
+        if options.link_to_first {
+            if let Some(first) = self.first_leaf(&index_link) {
+                let (path, source_path) = (first.path.clone(), first.source_path.clone());
+                index_link.path = path;
+                index_link.source_path = source_path;
+            }
+        }
+
+        if let (Some(title), Some(_)) = (&options.overview, dir_rule) {
+            index_link.children.insert(
+                0,
+                Link {
+                    title: title.clone(),
+                    path: index_path,
+                    source_path: index_source,
+                    ..Link::default()
+                },
+            );
+        }
+
+        // Cleared last, so that an overview still links to the index page
+        if options.unlinked {
+            index_link.path = String::new();
+            index_link.source_path = None;
+        }
+
+        Ok(index_link)
+    }
+
+    /// Whether the path points to the index page of a directory
+    fn is_index_file(&self, path: &Path) -> bool {
+        self.config.is_index_file(path) || path.file_name() == Some(OsStr::new("index.md"))
+    }
+
+    /// Adds the link to the level, unless a link to the same page is already
+    /// there. The first occurrence wins, and the duplicate is reported as a
+    /// warning.
+    fn push_unique(&self, links: &mut Vec<Link>, link: Link, path: &Path) {
+        if links.iter().any(|l| l.path == link.path) {
+            self.warn(format!(
+                "Found {} more than once in the navigation rules. \
+                 Only the first one will be shown.",
+                path.display()
+            ));
+        } else {
+            links.push(link);
+        }
+    }
+
+    /// Matches a path provided in a NavRule to a Link. Recursively searches through
+    /// the link children to find a match.
+    ///
+    /// Links are first matched by the URI they are served from. If none
+    /// match, the URI the page would have had without a slug is tried, so
+    /// that rules keep pointing at the file they were written against.
+    fn find_matching_link(&self, path: &Path, links: &[Link]) -> Result<Link, NavigationError> {
+        let uri = self.rule_uri(path);
+
+        let found = Self::search(&uri, links, Link::matches_path)
+            .or_else(|| Self::search(&uri, links, Link::matches_unslugged_uri));
+
+        match found {
+            Some(link) => Ok(link.clone()),
+            None => Err(NavigationError::NotFound {
+                path: path.to_path_buf(),
+                candidates: Self::candidates_for(&uri, links),
+                suggestions: self.suggestions_for(&uri, links),
+            }),
+        }
+    }
+
+    /// Converts a path in a NavRule, which includes the docs directory, into
+    /// the URI of the page it points to.
+    fn rule_uri(&self, path: &Path) -> String {
+        // Paths written on Windows may use backslashes as separators
+        let path = PathBuf::from(path.to_string_lossy().replace('\\', "/"));
+        let without_docs_part = path
+            .strip_prefix(self.config.docs_dir_name())
+            .unwrap_or(&path);
+
+        self.strip_language(Link::path_to_uri(without_docs_part, self.config))
+    }
+
+    /// The URI the document will be linked to from the navigation
+    fn uri_for(&self, doc: &Document) -> String {
+        self.strip_language(doc.uri_path(self.config))
+    }
+
+    /// Removes the language directory from the start of the URI, keeping
+    /// the base path in front of it
+    fn strip_language(&self, uri: String) -> String {
+        let language = match &self.language {
+            Some(language) => language,
+            None => return uri,
+        };
+
+        let base_path = self.config.base_path();
+        let rest = match uri[base_path.len()..]
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_prefix(language.as_str()))
+        {
+            Some(rest) => rest,
+            None => return uri,
+        };
+
+        if rest.is_empty() || rest == "/" {
+            Link::root_uri(self.config)
+        } else if rest.starts_with('/') {
+            format!("{}{}", base_path, rest)
+        } else {
+            uri
+        }
+    }
+
+    /// Collects all links in the tree whose path matches the glob pattern,
+    /// without their children. A `*` only matches the pages at its level,
+    /// and directories are only matched by patterns with a `**`.
+    fn collect_glob_matches(pattern: &str, links: &[Link], matches: &mut Vec<Link>) {
+        let recursive = pattern.contains("**");
+
+        for link in links {
+            if (recursive || !link.is_section()) && glob_matches(pattern, &link.path) {
+                let mut link = link.clone();
+                link.children.truncate(0);
+                matches.push(link);
+            }
+
+            Self::collect_glob_matches(pattern, &link.children, matches);
+        }
+    }
+
+    /// Collects the pages nested anywhere under the given links. Sections
+    /// are only descended into, and group headings and dividers are left
+    /// out.
+    fn collect_pages(links: &[Link], pages: &mut Vec<Link>) {
+        for link in links {
+            if link.is_section() {
+                Self::collect_pages(&link.children, pages);
+            } else if link.is_leaf() {
+                pages.push(link.clone());
+            }
+        }
+    }
+
+    /// Looks for a link matching the given URI. Checks the current level
+    /// first, before descending into the children of each link.
+    fn search<'l>(
+        uri: &str,
+        links: &'l [Link],
+        matches: fn(&Link, &str) -> bool,
+    ) -> Option<&'l Link> {
+        links.iter().find(|link| matches(link, uri)).or_else(|| {
+            links
+                .iter()
+                .find_map(|l| Self::search(uri, &l.children, matches))
+        })
+    }
+
+    /// Finds up to three links anywhere in the tree whose path is only a few
+    /// typos away from the URI, closest first
+    fn suggestions_for(&self, uri: &str, links: &[Link]) -> Vec<String> {
+        let max_distance = std::cmp::max(2, uri.chars().count() / 5);
+
+        let mut close = self
+            .all_paths(links)
+            .into_iter()
+            .map(|path| (edit_distance(uri, &path), path))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect::<Vec<_>>();
+
+        close.sort();
+        close.into_iter().take(3).map(|(_, path)| path).collect()
+    }
+
+    /// Lists the paths of the links that live on the same level as the
+    /// given URI, so that we can tell the user what they could have meant.
+    fn candidates_for(uri: &str, links: &[Link]) -> Vec<String> {
+        let uri = uri.trim_end_matches('/');
+        let level = match uri.rfind('/') {
+            Some(0) | None => Some(links),
+            Some(i) => Self::search(&uri[..i], links, Link::matches_path)
+                .map(|parent| &parent.children[..]),
+        };
+
+        level
+            .unwrap_or(links)
+            .iter()
+            .map(|l| l.path.clone())
+            .collect()
+    }
+}
+
+/// The differences between two navigation trees, keyed by the path of
+/// each link
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NavDiff {
+    /// Links only in the new tree
+    pub added: BTreeMap<String, Link>,
+    /// Links only in the old tree
+    pub removed: BTreeMap<String, Link>,
+    /// Links in both trees, but under a different parent
+    pub moved: BTreeMap<String, MovedLink>,
+}
+
+impl NavDiff {
+    /// Whether the two trees have the same links under the same parents
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+}
+
+/// A link nested under a different parent in the new tree. The parents
+/// are given by their path, and are `None` on the top level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MovedLink {
+    pub link: Link,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// The ways a level of the navigation can be sorted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortMode {
+    /// Links with an `order` first, then the rest by title
+    Alphanumeric,
+    /// Like `Alphanumeric`, but with the titles from Z to A
+    AlphanumericDesc,
+    /// The exact reverse of `Alphanumeric`
+    Reverse,
+    /// Newest first, leaving links without a date last
+    Date,
+    /// Only by title, ignoring any `order`
+    Title,
+    /// Like `Alphanumeric`, but comparing version numbers in the titles
+    /// part by part, so that "v1.9" comes before "v1.10"
+    Semver,
+    /// Links with an `order` first. The rest keep their position relative
+    /// to each other.
+    Order,
+    /// Links stay in the order they were declared in
+    AsDeclared,
+}
+
+impl SortMode {
+    /// The names a `sort` can be set to
+    pub const NAMES: [&'static str; 6] = ["title", "order", "reverse", "date", "semver", "none"];
+
+    /// The mode for a `sort` set on a navigation rule or an index page
+    pub fn from_name(name: &str) -> Option<SortMode> {
+        match name {
+            "title" => Some(SortMode::Title),
+            "order" => Some(SortMode::Order),
+            "reverse" => Some(SortMode::Reverse),
+            "date" => Some(SortMode::Date),
+            "semver" => Some(SortMode::Semver),
+            "none" => Some(SortMode::AsDeclared),
+            _ => None,
+        }
+    }
+}
+
+/// Where the directories of a level go in the generated navigation,
+/// relative to its pages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupOrder {
+    /// Pages first, then directories, each sorted on their own
+    FilesFirst,
+    /// Directories first, then pages, each sorted on their own
+    DirsFirst,
+    /// Pages and directories sorted together
+    Mixed,
+}
+
+impl GroupOrder {
+    /// The order for a `group_order` set in doctave.yaml
+    pub fn from_name(name: &str) -> Option<GroupOrder> {
+        match name {
+            "files_first" => Some(GroupOrder::FilesFirst),
+            "dirs_first" => Some(GroupOrder::DirsFirst),
+            "mixed" => Some(GroupOrder::Mixed),
+            _ => None,
+        }
+    }
+}
+
+/// Keeps a page at the top or bottom of its level, whatever the sort
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pin {
+    Top,
+    Bottom,
+}
+
+impl Pin {
+    /// The pin for a `pin` set in the frontmatter
+    pub fn from_name(name: &str) -> Option<Pin> {
+        match name {
+            "top" => Some(Pin::Top),
+            "bottom" => Some(Pin::Bottom),
+            _ => None,
+        }
+    }
+}
+
+/// Sorts a single level of links, leaving their children as they are.
+/// Pinned links are moved to the top or bottom afterwards, keeping the
+/// order they were sorted in among themselves.
+pub fn sort_links(links: &mut [Link], mode: SortMode) {
+    match mode {
+        SortMode::Alphanumeric => links.sort_by(Link::compare),
+        SortMode::AlphanumericDesc => links.sort_by(Link::compare_descending),
+        SortMode::Reverse => links.sort_by(|a, b| Link::compare(b, a)),
+        SortMode::Date => links.sort_by(Link::compare_dates),
+        SortMode::Title => links.sort_by(|a, b| {
+            alphanumeric_sort::compare_str(&a.title, &b.title).then_with(|| a.path.cmp(&b.path))
+        }),
+        SortMode::Semver => links.sort_by(Link::compare_versions),
+        SortMode::Order => links.sort_by(Link::compare_orders),
+        SortMode::AsDeclared => return,
+    }
+
+    links.sort_by_key(|link| match link.pin {
+        Some(Pin::Top) => 0,
+        None => 1,
+        Some(Pin::Bottom) => 2,
+    });
+}
+
+/// The plain ASCII version of common accented letters
+fn transliterate(c: char) -> Option<&'static str> {
+    let plain = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => "e",
+        'ğ' => "g",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'ı' => "i",
+        'ł' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'œ' => "oe",
+        'ř' => "r",
+        'ß' => "ss",
+        'ś' | 'š' | 'ş' => "s",
+        'ť' | 'ţ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => "u",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+
+    Some(plain)
+}
+
+/// Compares two URIs, ignoring case and any trailing slashes. Rules point to
+/// directories without one, while index pages may end in a `/` depending on
+/// the `trailing_slash` setting. Case is ignored since paths in doctave.yaml
+/// may not match the case of the file on case-insensitive filesystems.
+fn same_uri(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/').to_lowercase() == b.trim_end_matches('/').to_lowercase()
+}
+
+/// Matches a URI against a glob pattern, segment by segment. A `*` matches
+/// any characters inside a segment, while a `**` segment matches any number
+/// of segments. A trailing `**` has to match at least one segment, so that
+/// `/api/**` does not match the `/api` index itself. Like `same_uri`, the
+/// comparison ignores case.
+fn glob_matches(pattern: &str, uri: &str) -> bool {
+    fn segments_match(pattern: &[&str], uri: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => uri.is_empty(),
+            Some((&"**", [])) => !uri.is_empty(),
+            Some((&"**", rest)) => {
+                segments_match(rest, uri) || (!uri.is_empty() && segments_match(pattern, &uri[1..]))
+            }
+            Some((first, rest)) => match uri.split_first() {
+                Some((segment, uri_rest)) => {
+                    segment_matches(first.as_bytes(), segment.as_bytes())
+                        && segments_match(rest, uri_rest)
+                }
+                None => false,
+            },
+        }
+    }
+
+    fn segment_matches(pattern: &[u8], segment: &[u8]) -> bool {
+        match pattern.split_first() {
+            None => segment.is_empty(),
+            Some((b'*', rest)) => (0..=segment.len()).any(|i| segment_matches(rest, &segment[i..])),
+            Some((c, rest)) => segment.first() == Some(c) && segment_matches(rest, &segment[1..]),
+        }
+    }
+
+    let pattern = pattern.to_lowercase();
+    let uri = uri.trim_end_matches('/').to_lowercase();
+
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    let uri = uri.split('/').collect::<Vec<_>>();
+
+    segments_match(&pattern, &uri)
+}
+
+/// The number of single character insertions, deletions and substitutions
+/// needed to turn one string into the other
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            let deletion = previous[j + 1] + 1;
+            let insertion = current[j] + 1;
+
+            current.push(substitution.min(deletion).min(insertion));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Describes a reason why the navigation tree could not be built
+#[derive(Debug, Clone, PartialEq)]
+pub enum NavigationError {
+    /// A rule pointed to a path that didn't match any document
+    NotFound {
+        path: PathBuf,
+        candidates: Vec<String>,
+        /// Links anywhere in the tree with a similar path
+        suggestions: Vec<String>,
+    },
+    /// A directory was nested under itself in the navigation rules. The
+    /// chain lists the rules from the directory down to where it repeats.
+    Cycle { chain: Vec<PathBuf> },
+    /// More than one of the directories merged into the navigation had a
+    /// link to the same path
+    Collision { path: String },
+    /// A named navigation menu was requested, but not set in doctave.yaml
+    UnknownMenu {
+        name: String,
+        available: Vec<String>,
+    },
+    /// The navigation was built for a language without a directory
+    UnknownLanguage { language: String },
+    /// Two documents would be served from the same URI
+    DuplicateUri {
+        uri: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+}
+
+impl fmt::Display for NavigationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NavigationError::NotFound {
+                path,
+                candidates,
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "Could not find a page matching {} in the navigation rules.",
+                    path.display()
+                )?;
+
+                if !suggestions.is_empty() {
+                    write!(f, "\nDid you mean {}?", suggestions.join(" or "))?;
+                }
+
+                if !candidates.is_empty() {
+                    write!(f, "\nThe available links at that level were:")?;
+
+                    for candidate in candidates {
+                        write!(f, "\n  * {}", candidate)?;
+                    }
+                }
+
+                Ok(())
+            }
+            NavigationError::Cycle { chain } => write!(
+                f,
+                "Found a directory nested under itself in the navigation rules: {}",
+                chain
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            ),
+            NavigationError::Collision { path } => write!(
+                f,
+                "Found more than one page for {} while merging directories into the navigation.",
+                path
+            ),
+            NavigationError::DuplicateUri { uri, first, second } => write!(
+                f,
+                "Found two pages served from {}: {} and {}. Rename or move one of them.",
+                uri,
+                first.display(),
+                second.display()
+            ),
+            NavigationError::UnknownLanguage { language } => write!(
+                f,
+                "Could not find a directory for the language {} in the docs folder.",
+                language
+            ),
+            NavigationError::UnknownMenu { name, available } => {
+                write!(
+                    f,
+                    "Could not find a navigation named {} in doctave.yaml.",
+                    name
+                )?;
+
+                if !available.is_empty() {
+                    write!(
+                        f,
+                        "\nThe available navigations are: {}",
+                        available.join(", ")
+                    )?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for NavigationError {}
+
+/// The version of the format the navigation is exported in. Bump it when
+/// the serialized fields of `Link` change, so that tools reading nav.json
+/// can tell the formats apart.
+pub const NAV_SCHEMA_VERSION: u32 = 5;
+
+/// The navigation tree as written to nav.json
+#[derive(Debug, Serialize)]
+pub struct NavigationExport<'l> {
+    pub schema_version: u32,
+    pub links: &'l [Link],
+}
+
+impl<'l> NavigationExport<'l> {
+    pub fn new(links: &'l [Link]) -> Self {
+        NavigationExport {
+            schema_version: NAV_SCHEMA_VERSION,
+            links,
+        }
+    }
+}
+
+/// A link in the navigation. The serialized fields are part of the nav.json
+/// format, see `NAV_SCHEMA_VERSION`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Link {
+    pub path: String,
+    pub title: String,
+    /// The original title, if `title` had to be shortened to fit in the
+    /// navigation
+    pub title_full: Option<String>,
+    /// Shown next to the title. Set via the `icon` frontmatter key, or in
+    /// the navigation rules.
+    pub icon: Option<String>,
+    /// A short summary of the page. Set via the `description` frontmatter
+    /// key, or in the navigation rules.
+    pub description: Option<String>,
+    /// Short labels like "New" or "Beta" shown next to the title. Set via
+    /// the `badges` frontmatter key, or in the navigation rules.
+    pub badges: Vec<String>,
+    /// Where the link opens, like `_blank` for a new tab. Only set for
+    /// links added in the navigation rules.
+    pub target: Option<String>,
+    /// CSS classes for the link, set in the navigation rules
+    pub classes: Vec<String>,
+    /// Position set via the `order` frontmatter key. Directories use the
+    /// value from their index page.
+    pub order: Option<i64>,
+    /// Whether the children of this link should be shown by default
+    pub expanded: bool,
+    /// Whether this link points to the page being rendered
+    pub active: bool,
+    /// Whether the page being rendered is nested under this link
+    pub active_trail: bool,
+    /// How deeply the link is nested, starting from 0 at the top level.
+    /// Only set once `annotate_depth` has been called on the tree.
+    pub depth: usize,
+    /// The id of the link in the rendered navigation. Only set once
+    /// `annotate_ids` has been called on the tree.
+    pub id: String,
+    /// Whether this is a divider between other links, rather than a link.
+    /// Dividers have no path or title.
+    pub divider: bool,
+    /// The URI the page would have without a slug, if it has one.
+    /// Navigation rules refer to pages by their file path, so they are
+    /// matched against this too.
+    #[serde(skip)]
+    pub unslugged_uri: Option<String>,
+    /// The file the page is built from, relative to the project root, like
+    /// `docs/one.md`, for linking to the file in the repository. Links
+    /// added by navigation rules, like external links, have none.
+    pub source_path: Option<PathBuf>,
+    /// Set via the `pin` frontmatter key to keep the link at the top or
+    /// bottom of its level
+    #[serde(skip)]
+    pub pin: Option<Pin>,
+    /// The date of the page, used when its directory is sorted by date
+    #[serde(skip)]
+    pub date: Option<(u32, u32, u32)>,
+    pub children: Vec<Link>,
+}
+
+impl Default for Link {
+    fn default() -> Self {
+        Link {
+            path: String::new(),
+            title: String::new(),
+            title_full: None,
+            icon: None,
+            description: None,
+            badges: vec![],
+            classes: vec![],
+            target: None,
+            order: None,
+            expanded: true,
+            active: false,
+            active_trail: false,
+            depth: 0,
+            id: String::new(),
+            divider: false,
+            unslugged_uri: None,
+            source_path: None,
+            pin: None,
+            date: None,
+            children: vec![],
+        }
+    }
+}
+
+impl Link {
+    /// Whether the link points outside of the documentation site
+    pub fn is_external(&self) -> bool {
+        !self.path.is_empty() && !self.path.starts_with('/')
+    }
+
+    /// Whether the link has other links nested under it. The link may still
+    /// point to a page of its own, like the index of a directory.
+    pub fn is_section(&self) -> bool {
+        !self.children.is_empty()
+    }
+
+    /// Whether the link is a plain page, with no links nested under it.
+    /// Group headings and dividers are never leaves.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty() && !self.is_group() && !self.divider
+    }
+
+    /// Whether the link points to a heading inside a page
+    pub fn is_anchor(&self) -> bool {
+        self.path.contains('#')
+    }
+
+    /// Whether the link is a group heading, which doesn't point to a page
+    pub fn is_group(&self) -> bool {
+        self.path.is_empty() && !self.divider
+    }
+
+    /// Whether the link points to the given URI
+    fn matches_path(&self, uri: &str) -> bool {
+        same_uri(&self.path, uri)
+    }
+
+    /// Whether the link was built from a page that would have been served
+    /// from the given URI, if it didn't have a slug
+    fn matches_unslugged_uri(&self, uri: &str) -> bool {
+        self.unslugged_uri
+            .as_deref()
+            .map_or(false, |s| same_uri(s, uri))
+    }
+
+    /// Overrides the title and icon with the ones set in a navigation rule
+    fn apply(&mut self, options: &LinkOptions) {
+        if let Some(title) = &options.title {
+            self.title = title.clone();
+        }
+        if let Some(icon) = &options.icon {
+            self.icon = Some(icon.clone());
+        }
+        if let Some(description) = &options.description {
+            self.description = Some(description.clone());
+        }
+        if let Some(badges) = &options.badges {
+            self.badges = badges.clone();
+        }
+        if !options.classes.is_empty() {
+            self.classes = options.classes.clone();
+        }
+    }
+
+    /// Cuts the title down to the given number of characters followed by an
+    /// ellipsis, if it is any longer
+    fn truncate_title(&mut self, max: usize) {
+        if self.title.chars().count() <= max {
+            return;
+        }
+
+        let short = self.title.chars().take(max).collect::<String>();
+        let short = format!("{}…", short.trim_end());
+
+        self.title_full = Some(std::mem::replace(&mut self.title, short));
+    }
+
+    /// A copy of the link, without any of its children
+    pub fn without_children(&self) -> Link {
+        Link {
+            path: self.path.clone(),
+            title: self.title.clone(),
+            title_full: self.title_full.clone(),
+            icon: self.icon.clone(),
+            description: self.description.clone(),
+            badges: self.badges.clone(),
+            classes: self.classes.clone(),
+            target: self.target.clone(),
+            order: self.order,
+            expanded: self.expanded,
+            active: self.active,
+            active_trail: self.active_trail,
+            depth: self.depth,
+            id: self.id.clone(),
+            divider: self.divider,
+            unslugged_uri: self.unslugged_uri.clone(),
+            source_path: self.source_path.clone(),
+            pin: self.pin,
+            date: self.date,
+            children: vec![],
+        }
+    }
+
+    /// Sorts links with an explicit `order` first, in ascending order.
+    /// Links with equal or missing orders fall back to sorting
+    /// alphanumerically by title. Links with the same title are sorted by
+    /// path, so that the order doesn't depend on the filesystem.
+    fn compare(a: &Link, b: &Link) -> Ordering {
+        Link::compare_orders(a, b)
+            .then_with(|| alphanumeric_sort::compare_str(&a.title, &b.title))
+            .then_with(|| a.path.cmp(&b.path))
+    }
+
+    /// Like `compare`, but sorts the titles in descending order. Links with
+    /// an explicit `order` still come first, in ascending order.
+    fn compare_descending(a: &Link, b: &Link) -> Ordering {
+        Link::compare_orders(a, b)
+            .then_with(|| alphanumeric_sort::compare_str(&b.title, &a.title))
+            .then_with(|| a.path.cmp(&b.path))
+    }
+
+    /// Like `compare`, but titles containing a version number are compared
+    /// by it, part by part. Titles without one are listed after them.
+    fn compare_versions(a: &Link, b: &Link) -> Ordering {
+        let version = |link: &Link| Link::version_in(&link.title);
+
+        Link::compare_orders(a, b)
+            .then_with(|| match (version(a), version(b)) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+            .then_with(|| alphanumeric_sort::compare_str(&a.title, &b.title))
+            .then_with(|| a.path.cmp(&b.path))
+    }
+
+    /// The parts of the first version number in the title, like `[1, 10]`
+    /// for "Release v1.10"
+    fn version_in(title: &str) -> Option<Vec<u64>> {
+        let start = title.find(|c: char| c.is_ascii_digit())?;
+
+        let parts = title[start..]
+            .split(|c: char| !c.is_ascii_digit() && c != '.')
+            .next()?
+            .split('.')
+            .take_while(|part| !part.is_empty())
+            .filter_map(|part| part.parse().ok())
+            .collect();
+
+        Some(parts)
+    }
+
+    fn compare_orders(a: &Link, b: &Link) -> Ordering {
+        match (a.order, b.order) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    /// Sorts links by date, newest first. Links without a date are listed
+    /// last, falling back to the regular sorting.
+    fn compare_dates(a: &Link, b: &Link) -> Ordering {
+        match (a.date, b.date) {
+            (Some(x), Some(y)) if x != y => y.cmp(&x),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            _ => Link::compare(a, b),
+        }
+    }
+
+    /// Converts a path to a file inside the docs or site directory into the
+    /// URI the page will be served from.
+    ///
+    /// Index pages always map to the path of their directory. Other pages
+    /// have their extension stripped, unless `clean_urls` has been disabled
+    /// in the config, in which case they point to the `.html` file.
+    ///
+    /// With `trailing_slash` enabled, index pages end in a `/`. The root
+    /// is always `/`.
+    ///
+    /// A `base_path` in the config is put in front of every URI, so the
+    /// root becomes e.g. `/docs/` and `/one` becomes `/docs/one`.
+    pub fn path_to_uri(path: &Path, config: &Config) -> String {
+        let index_dir = Link::strip_index(path, config);
+        let is_index = index_dir.is_some();
+
+        let tmp = match index_dir {
+            Some(dir) => dir,
+            None => {
+                let mut tmp = path.to_owned();
+
+                if tmp.extension().is_some() {
+                    if config.clean_urls() {
+                        tmp.set_extension("");
+                    } else {
+                        tmp.set_extension("html");
+                    }
+                }
+
+                tmp
+            }
+        };
+
+        let uri_path = Link::to_forward_slashes(&tmp);
+
+        if uri_path.is_empty() {
+            format!("{}/", config.base_path())
+        } else if is_index && config.trailing_slash() {
+            format!("{}/{}/", config.base_path(), uri_path)
+        } else {
+            format!("{}/{}", config.base_path(), uri_path)
+        }
+    }
+
+    /// Gives the directory of an index page, like `child` for
+    /// `child/index.html` or for a `child/README.md` when it is one of the
+    /// `index_filenames`. Other pages give `None`. Windows-style paths with
+    /// backslashes are understood on every platform.
+    pub fn strip_index(path: &Path, config: &Config) -> Option<PathBuf> {
+        let path = PathBuf::from(path.to_string_lossy().replace('\\', "/"));
+
+        if path.file_stem() == Some(OsStr::new("index")) || config.is_index_file(&path) {
+            Some(path.parent().map(|p| p.to_owned()).unwrap_or_default())
+        } else {
+            None
+        }
+    }
+
+    /// Joins the parts of the path with forward slashes, since URIs work
+    /// the same across all platforms. Backslashes, as in Windows-style
+    /// paths, are treated as separators too.
+    pub fn to_forward_slashes(path: &Path) -> String {
+        PathBuf::from(path.to_string_lossy().replace('\\', "/"))
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// The URI of the root page of the site
+    pub fn root_uri(config: &Config) -> String {
+        format!("{}/", config.base_path())
+    }
+
+    /// Resolves a relative link in a Markdown page, like `../three.md`, to
+    /// the URI the target page will be served from. The source is the path
+    /// of the linking page inside the docs directory. Links to an index page,
+    /// like a `README.md`, point at the directory, and any `#fragment` is kept as is.
+    pub fn resolve_relative(source: &Path, target: &str, config: &Config) -> String {
+        let (target, fragment) = match target.find('#') {
+            Some(i) => target.split_at(i),
+            None => (target, ""),
+        };
+
+        let mut path = source.parent().map(|p| p.to_owned()).unwrap_or_default();
+
+        for component in Path::new(target).components() {
+            match component {
+                Component::ParentDir => {
+                    path.pop();
+                }
+                Component::Normal(part) => path.push(part),
+                _ => {}
+            }
+        }
+
+        if config.is_index_file(&path) {
+            path.set_file_name("index.html");
+        } else if path.extension() == Some(OsStr::new("md")) {
+            path.set_extension("html");
+        }
+
+        format!("{}{}", Link::path_to_uri(&path, config), fragment)
+    }
+
+    /// Turns a title into something that can be used in a URI. Letters are
+    /// lowercased and common accented characters are replaced with their
+    /// plain counterparts. Any other characters are collapsed into single
+    /// hyphens between words.
+    pub fn slugify(title: &str) -> String {
+        let mut slug = String::with_capacity(title.len());
+        let mut separate = false;
+
+        for c in title.chars().flat_map(char::to_lowercase) {
+            let mut buf = [0; 4];
+            let part = if c.is_ascii_alphanumeric() {
+                &*c.encode_utf8(&mut buf)
+            } else if let Some(plain) = transliterate(c) {
+                plain
+            } else {
+                separate = true;
+                continue;
+            };
+
+            if separate && !slug.is_empty() {
+                slug.push('-');
+            }
+            separate = false;
+            slug.push_str(part);
+        }
+
+        slug
+    }
+
+    /// Capitalizes each word of the title, treating hyphens as spaces.
+    /// Small connecting words like "of" or "the" are lowercased, unless
+    /// they start the title. Other letters are left alone, so acronyms
+    /// like "API" keep their case.
+    pub fn title_case(title: &str) -> String {
+        const SMALL_WORDS: &[&str] = &[
+            "a", "an", "and", "as", "at", "but", "by", "for", "in", "nor", "of", "on", "or", "the",
+            "to", "with",
+        ];
+
+        title
+            .replace('-', " ")
+            .split_whitespace()
+            .enumerate()
+            .map(|(i, word)| {
+                let lowercase = word.to_lowercase();
+
+                if i > 0 && SMALL_WORDS.contains(&lowercase.as_str()) {
+                    return lowercase;
+                }
+
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Turns a title taken from a Markdown heading into plain text, so that
+    /// a heading like ``# `serde` Integration`` reads "serde Integration".
+    /// Code spans keep their content as is, emphasis markers are dropped,
+    /// and links are replaced by their text. Underscores inside words, like
+    /// in `snake_case`, and asterisks that don't mark emphasis, like in
+    /// `2 * 3`, are kept.
+    pub fn strip_markdown(title: &str) -> String {
+        let chars = title.chars().collect::<Vec<_>>();
+        let mut plain = String::with_capacity(title.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '`' => {
+                    let ticks = chars[i..].iter().take_while(|c| **c == '`').count();
+                    let start = i + ticks;
+
+                    match Self::closing_ticks(&chars, start, ticks) {
+                        Some(end) => {
+                            plain.extend(&chars[start..end]);
+                            i = end + ticks;
+                        }
+                        None => {
+                            plain.extend(&chars[i..start]);
+                            i = start;
+                        }
+                    }
+                }
+                '[' => match Self::link_end(&chars, i) {
+                    Some((text_end, end)) => {
+                        let text = chars[i + 1..text_end].iter().collect::<String>();
+                        plain.push_str(&Self::strip_markdown(&text));
+                        i = end + 1;
+                    }
+                    None => {
+                        plain.push('[');
+                        i += 1;
+                    }
+                },
+                '\\' if i + 1 < chars.len() && chars[i + 1].is_ascii_punctuation() => {
+                    plain.push(chars[i + 1]);
+                    i += 2;
+                }
+                '*' => {
+                    let stars = chars[i..].iter().take_while(|c| **c == '*').count();
+                    let start = i + stars;
+
+                    match Self::closing_stars(&chars, start, stars) {
+                        Some(end) => {
+                            let text = chars[start..end].iter().collect::<String>();
+                            plain.push_str(&Self::strip_markdown(&text));
+                            i = end + stars;
+                        }
+                        None => {
+                            plain.extend(&chars[i..start]);
+                            i = start;
+                        }
+                    }
+                }
+                '_' => {
+                    let inside_word = i > 0
+                        && chars[i - 1].is_alphanumeric()
+                        && chars
+                            .get(i + 1)
+                            .map(|c| c.is_alphanumeric())
+                            .unwrap_or(false);
+
+                    if inside_word {
+                        plain.push('_');
+                    }
+                    i += 1;
+                }
+                c => {
+                    plain.push(c);
+                    i += 1;
+                }
+            }
+        }
+
+        plain.trim().to_owned()
+    }
+
+    /// Finds where a code span opened with `ticks` backticks ends. Only a
+    /// run of exactly as many backticks closes it.
+    fn closing_ticks(chars: &[char], start: usize, ticks: usize) -> Option<usize> {
+        let mut i = start;
+
+        while i < chars.len() {
+            if chars[i] == '`' {
+                let run = chars[i..].iter().take_while(|c| **c == '`').count();
+                if run == ticks {
+                    return Some(i);
+                }
+                i += run;
+            } else {
+                i += 1;
+            }
+        }
+
+        None
+    }
+
+    /// Finds where emphasis opened with `stars` asterisks ends. Like in
+    /// Markdown, the opening run has to be followed by a non-space, and only
+    /// a run of exactly as many asterisks right after a non-space closes it.
+    /// Asterisks without a match, like in `2 * 3`, are plain text.
+    fn closing_stars(chars: &[char], start: usize, stars: usize) -> Option<usize> {
+        if chars.get(start).map_or(true, |c| c.is_whitespace()) {
+            return None;
+        }
+
+        let mut i = start + 1;
+
+        while i < chars.len() {
+            match chars[i] {
+                '\\' => i += 2,
+                '*' => {
+                    let run = chars[i..].iter().take_while(|c| **c == '*').count();
+                    if run == stars && !chars[i - 1].is_whitespace() {
+                        return Some(i);
+                    }
+                    i += run;
+                }
+                _ => i += 1,
+            }
+        }
+
+        None
+    }
+
+    /// For a `[text](url)` starting at `start`, finds the closing bracket of
+    /// the text and the closing parenthesis of the URL.
+    fn link_end(chars: &[char], start: usize) -> Option<(usize, usize)> {
+        let text_end = start + chars[start..].iter().position(|c| *c == ']')?;
+
+        if chars.get(text_end + 1) != Some(&'(') {
+            return None;
+        }
+
+        let end = text_end + chars[text_end..].iter().position(|c| *c == ')')?;
+
+        Some((text_end, end))
+    }
+
+    /// An id for the link that can be used in the DOM, derived from its
+    /// path. The root page is `nav-root`, and every part of other paths
+    /// follows a `--`, so that `/child/nested-four` becomes
+    /// `nav--child--nested-four`. Letters, digits and single hyphens
+    /// between them are kept, and other characters are written as their
+    /// hex code between underscores, so two paths never share an id.
+    /// Groups have no path, so their title is used instead.
+    pub fn dom_id(&self) -> String {
+        if self.divider {
+            return String::from("nav-divider");
+        }
+
+        if self.is_group() {
+            return format!("nav-group--{}", Link::dom_id_part(&self.title));
+        }
+
+        let path = self.path.trim_start_matches('/').trim_end_matches('/');
+
+        if path.is_empty() {
+            return String::from("nav-root");
+        }
+
+        path.split('/').fold(String::from("nav"), |mut id, part| {
+            id.push_str("--");
+            id.push_str(&Link::dom_id_part(part));
+            id
+        })
+    }
+
+    fn dom_id_part(part: &str) -> String {
+        let chars = part.chars().collect::<Vec<_>>();
+        let mut id = String::with_capacity(part.len());
+
+        for (i, c) in chars.iter().enumerate() {
+            let between_alphanumerics = i > 0
+                && chars[i - 1].is_ascii_alphanumeric()
+                && chars
+                    .get(i + 1)
+                    .map_or(false, |next| next.is_ascii_alphanumeric());
+
+            if c.is_ascii_alphanumeric() || (*c == '-' && between_alphanumerics) {
+                id.push(*c);
+            } else {
+                id.push_str(&format!("_{:x}_", *c as u32));
+            }
+        }
+
+        id
+    }
+
+    /// The URI of a file that is copied to the site as is, like an image in
+    /// the `_include` directory, under the given base path.
+    pub fn path_to_uri_with_extension(path: &Path, base_path: &str) -> String {
+        let mut tmp = path.to_owned();
+
+        if tmp.file_name() == Some(OsStr::new("index")) {
+            tmp = tmp
+                .parent()
+                .map(|p| p.to_owned())
+                .unwrap_or_else(|| PathBuf::from(""));
+        }
+
+        format!("{}/{}", base_path, Link::to_forward_slashes(&tmp))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::fs;
+    use std::path::Path;
+
+    use crate::Document;
+
+    fn page(path: &str, name: &str) -> Document {
+        let mut frontmatter = BTreeMap::new();
+        frontmatter.insert("title".to_string(), name.to_string());
+
+        Document::new(Path::new(path), "Not important".to_string(), frontmatter)
+    }
+
+    fn page_with(path: &str, name: &str, extra: &[(&str, &str)]) -> Document {
+        let mut frontmatter = BTreeMap::new();
+        frontmatter.insert("title".to_string(), name.to_string());
+        for (key, value) in extra {
+            frontmatter.insert(key.to_string(), value.to_string());
+        }
+
+        Document::new(Path::new(path), "Not important".to_string(), frontmatter)
+    }
+
+    /// A tree three levels deep used by several tests
+    fn nested_root() -> Directory {
+        Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("one.md", "One"),
+                page("two.md", "Two"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page("child/README.md", "Nested Root"),
+                    page("child/three.md", "Three"),
+                ],
+                dirs: vec![Directory {
+                    path: PathBuf::from("docs").join("child").join("nested"),
+                    docs: vec![
+                        page("child/nested/README.md", "Nested Root"),
+                        page("child/nested/four.md", "Four"),
+                    ],
+                    dirs: vec![],
+                }],
+            }],
+        }
+    }
+
+    fn config(yaml: Option<&str>) -> Config {
+        let conf = yaml.unwrap_or("---\ntitle: My project\n");
+
+        Config::from_yaml_str(&Path::new("project"), conf).unwrap()
+    }
+
+    #[test]
+    fn basic() {
+        let config = config(None);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("one.md", "One"),
+                page("two.md", "Two"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page("child/README.md", "Nested Root"),
+                    page("child/three.md", "Three"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let navigation = Navigation::new(&config);
+
+        assert_eq!(
+            navigation.build_for(&root).unwrap(),
+            vec![
+                Link {
+                    path: String::from("/child"),
+                    source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                    title: String::from("Nested Root"),
+                    children: vec![Link {
+                        path: String::from("/child/three"),
+                        source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                        title: String::from("Three"),
+                        children: vec![],
+                        ..Link::default()
+                    }],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/one"),
+                    source_path: Some(PathBuf::from("docs").join("one.md")),
+                    title: String::from("One"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/two"),
+                    source_path: Some(PathBuf::from("docs").join("two.md")),
+                    title: String::from("Two"),
+                    children: vec![],
+                    ..Link::default()
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn sorting_alphanumerically() {
+        let config = config(None);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("001.md", "bb"),
+                page("002.md", "11"),
+            ],
+            dirs: vec![
+                Directory {
+                    path: PathBuf::from("docs").join("bb_child"),
+                    docs: vec![
+                        page("child/README.md", "Index"),
+                        page("child/001.md", "BB"),
+                        page("child/002.md", "22"),
+                        page("child/003.md", "AA"),
+                        page("child/004.md", "11"),
+                    ],
+                    dirs: vec![],
+                },
+                Directory {
+                    path: PathBuf::from("docs").join("aa_child"),
+                    docs: vec![
+                        page("child2/README.md", "Index"),
+                        page("child2/001.md", "123"),
+                        page("child2/002.md", "aa"),
+                        page("child2/003.md", "cc"),
+                        page("child2/004.md", "bb"),
+                    ],
+                    dirs: vec![],
+                },
+            ],
+        };
+
+        let navigation = Navigation::new(&config);
+
+        assert_eq!(
+            navigation.build_for(&root).unwrap(),
+            vec![
+                Link {
+                    path: String::from("/002"),
+                    source_path: Some(PathBuf::from("docs").join("002.md")),
+                    title: String::from("11"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child"),
+                    source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                    title: String::from("Index"),
+                    children: vec![
+                        Link {
+                            path: String::from("/child/004"),
+                            source_path: Some(PathBuf::from("docs").join("child/004.md")),
+                            title: String::from("11"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                        Link {
+                            path: String::from("/child/002"),
+                            source_path: Some(PathBuf::from("docs").join("child/002.md")),
+                            title: String::from("22"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                        Link {
+                            path: String::from("/child/003"),
+                            source_path: Some(PathBuf::from("docs").join("child/003.md")),
+                            title: String::from("AA"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                        Link {
+                            path: String::from("/child/001"),
+                            source_path: Some(PathBuf::from("docs").join("child/001.md")),
+                            title: String::from("BB"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                    ],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child2"),
+                    source_path: Some(PathBuf::from("docs").join("child2/README.md")),
+                    title: String::from("Index"),
+                    children: vec![
+                        Link {
+                            path: String::from("/child2/001"),
+                            source_path: Some(PathBuf::from("docs").join("child2/001.md")),
+                            title: String::from("123"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                        Link {
+                            path: String::from("/child2/002"),
+                            source_path: Some(PathBuf::from("docs").join("child2/002.md")),
+                            title: String::from("aa"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                        Link {
+                            path: String::from("/child2/004"),
+                            source_path: Some(PathBuf::from("docs").join("child2/004.md")),
+                            title: String::from("bb"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                        Link {
+                            path: String::from("/child2/003"),
+                            source_path: Some(PathBuf::from("docs").join("child2/003.md")),
+                            title: String::from("cc"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                    ],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/001"),
+                    source_path: Some(PathBuf::from("docs").join("001.md")),
+                    title: String::from("bb"),
+                    children: vec![],
+                    ..Link::default()
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn sorting_by_order() {
+        let config = config(None);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("advanced.md", "Advanced Topics"),
+                page_with("install.md", "Install", &[("order", "2")]),
+                page_with("intro.md", "Introduction", &[("order", "1")]),
+                page("appendix.md", "Appendix"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page_with("child/README.md", "Nested Root", &[("order", "2")]),
+                    page("child/aa.md", "AA"),
+                    page_with("child/zz.md", "ZZ", &[("order", "1")]),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let navigation = Navigation::new(&config);
+
+        assert_eq!(
+            navigation.build_for(&root).unwrap(),
+            vec![
+                Link {
+                    path: String::from("/intro"),
+                    source_path: Some(PathBuf::from("docs").join("intro.md")),
+                    title: String::from("Introduction"),
+                    order: Some(1),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/install"),
+                    source_path: Some(PathBuf::from("docs").join("install.md")),
+                    title: String::from("Install"),
+                    order: Some(2),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child"),
+                    source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                    title: String::from("Nested Root"),
+                    order: Some(2),
+                    children: vec![
+                        Link {
+                            path: String::from("/child/zz"),
+                            source_path: Some(PathBuf::from("docs").join("child/zz.md")),
+                            title: String::from("ZZ"),
+                            order: Some(1),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                        Link {
+                            path: String::from("/child/aa"),
+                            source_path: Some(PathBuf::from("docs").join("child/aa.md")),
+                            title: String::from("AA"),
+                            order: None,
+                            children: vec![],
+                            ..Link::default()
+                        },
+                    ],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/advanced"),
+                    source_path: Some(PathBuf::from("docs").join("advanced.md")),
+                    title: String::from("Advanced Topics"),
+                    order: None,
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/appendix"),
+                    source_path: Some(PathBuf::from("docs").join("appendix.md")),
+                    title: String::from("Appendix"),
+                    order: None,
+                    children: vec![],
+                    ..Link::default()
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn manual_menu_simple() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("one.md", "One"),
+                page("two.md", "Two"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page("child/README.md", "Nested Root"),
+                    page("child/three.md", "Three"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs/one.md"), LinkOptions::default()),
+            NavRule::Dir(
+                PathBuf::from("docs/child"),
+                Some(DirIncludeRule::WildCard),
+                DirOptions::default(),
+            ),
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![
+                Link {
+                    path: String::from("/one"),
+                    source_path: Some(PathBuf::from("docs").join("one.md")),
+                    title: String::from("One"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child"),
+                    source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                    title: String::from("Nested Root"),
+                    children: vec![Link {
+                        path: String::from("/child/three"),
+                        source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                        title: String::from("Three"),
+                        children: vec![],
+                        ..Link::default()
+                    },],
+                    ..Link::default()
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn manual_menu_nested() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("one.md", "One"),
+                page("two.md", "Two"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page("child/README.md", "Nested Root"),
+                    page("child/three.md", "Three"),
+                ],
+                dirs: vec![Directory {
+                    path: PathBuf::from("docs").join("child").join("nested"),
+                    docs: vec![
+                        page("child/nested/README.md", "Nested Root"),
+                        page("child/nested/four.md", "Four"),
+                    ],
+                    dirs: vec![],
+                }],
+            }],
+        };
+
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+            NavRule::Dir(
+                PathBuf::from("docs").join("child"),
+                Some(DirIncludeRule::Explicit(vec![NavRule::Dir(
+                    PathBuf::from("docs").join("child").join("nested"),
+                    Some(DirIncludeRule::Explicit(vec![NavRule::File(
+                        PathBuf::from("docs")
+                            .join("child")
+                            .join("nested")
+                            .join("four.md"),
+                        LinkOptions::default(),
+                    )])),
+                    DirOptions::default(),
+                )])),
+                DirOptions::default(),
+            ),
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![
+                Link {
+                    path: String::from("/one"),
+                    source_path: Some(PathBuf::from("docs").join("one.md")),
+                    title: String::from("One"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child"),
+                    source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                    title: String::from("Nested Root"),
+                    children: vec![Link {
+                        path: String::from("/child/nested"),
+                        source_path: Some(PathBuf::from("docs").join("child/nested/README.md")),
+                        title: String::from("Nested Root"),
+                        children: vec![Link {
+                            path: String::from("/child/nested/four"),
+                            source_path: Some(PathBuf::from("docs").join("child/nested/four.md")),
+                            title: String::from("Four"),
+                            children: vec![],
+                            ..Link::default()
+                        },],
+                        ..Link::default()
+                    }],
+                    ..Link::default()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn manual_menu_file_from_nested_directory() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page("child/README.md", "Nested Root"),
+                    page("child/three.md", "Three"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs").join("child").join("three.md"),
+            LinkOptions::default(),
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![Link {
+                path: String::from("/child/three"),
+                source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                title: String::from("Three"),
+                children: vec![],
+                ..Link::default()
+            },]
+        );
+    }
+
+    #[test]
+    fn manual_menu_file_from_parent_directory() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started"), page("one.md", "One")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![page("child/README.md", "Nested Root")],
+                dirs: vec![],
+            }],
+        };
+
+        let rules = vec![NavRule::Dir(
+            PathBuf::from("docs").join("child"),
+            Some(DirIncludeRule::Explicit(vec![NavRule::File(
+                PathBuf::from("docs").join("one.md"),
+                LinkOptions::default(),
+            )])),
+            DirOptions::default(),
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![Link {
+                path: String::from("/child"),
+                source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                title: String::from("Nested Root"),
+                children: vec![Link {
+                    path: String::from("/one"),
+                    source_path: Some(PathBuf::from("docs").join("one.md")),
+                    title: String::from("One"),
+                    children: vec![],
+                    ..Link::default()
+                }],
+                ..Link::default()
+            },]
+        );
+    }
+
+    #[test]
+    fn manual_menu_file_title_override() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("install.md", "Comprehensive Installation Guide"),
+            ],
+            dirs: vec![],
+        };
+
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs").join("install.md"),
+            LinkOptions {
+                title: Some(String::from("Install")),
+                ..LinkOptions::default()
+            },
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![Link {
+                path: String::from("/install"),
+                source_path: Some(PathBuf::from("docs").join("install.md")),
+                title: String::from("Install"),
+                children: vec![],
+                ..Link::default()
+            }]
+        );
+        assert_eq!(
+            root.docs[1].title(),
+            "Comprehensive Installation Guide",
+            "Page title should not be affected by the override"
+        );
+    }
+
+    #[test]
+    fn manual_menu_missing_file() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started"), page("one.md", "One")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page("child/README.md", "Nested Root"),
+                    page("child/three.md", "Three"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs").join("child").join("thre.md"),
+            LinkOptions::default(),
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        let error = navigation.customize(&rules, &links).unwrap_err();
+
+        assert_eq!(
+            error,
+            NavigationError::NotFound {
+                path: PathBuf::from("docs").join("child").join("thre.md"),
+                candidates: vec![String::from("/child/three")],
+                suggestions: vec![String::from("/child/three")],
+            }
+        );
+
+        let message = format!("{}", error);
+        assert!(
+            message.contains(&format!(
+                "{}",
+                Path::new("docs").join("child").join("thre.md").display()
+            )),
+            "Error message was: {}",
+            message
+        );
+        assert!(
+            message.contains("/child/three"),
+            "Error message was: {}",
+            message
+        );
+    }
+
+    #[test]
+    fn manual_menu_glob() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("api"),
+                docs: vec![
+                    page("api/README.md", "API"),
+                    page("api/intro.md", "Introduction"),
+                    page("api/client.md", "Client"),
+                    page("api/auth.md", "Authentication"),
+                ],
+                dirs: vec![Directory {
+                    path: PathBuf::from("docs").join("api").join("nested"),
+                    docs: vec![
+                        page("api/nested/README.md", "Nested"),
+                        page("api/nested/deep.md", "Deep"),
+                    ],
+                    dirs: vec![],
+                }],
+            }],
+        };
+
+        let rules = vec![NavRule::Dir(
+            PathBuf::from("docs").join("api"),
+            Some(DirIncludeRule::Explicit(vec![
+                NavRule::File(
+                    PathBuf::from("docs").join("api").join("intro.md"),
+                    LinkOptions::default(),
+                ),
+                NavRule::Glob(PathBuf::from("docs").join("api").join("*.md")),
+            ])),
+            DirOptions::default(),
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![Link {
+                path: String::from("/api"),
+                source_path: Some(PathBuf::from("docs").join("api/README.md")),
+                title: String::from("API"),
+                children: vec![
+                    Link {
+                        path: String::from("/api/intro"),
+                        source_path: Some(PathBuf::from("docs").join("api/intro.md")),
+                        title: String::from("Introduction"),
+                        children: vec![],
+                        ..Link::default()
+                    },
+                    Link {
+                        path: String::from("/api/auth"),
+                        source_path: Some(PathBuf::from("docs").join("api/auth.md")),
+                        title: String::from("Authentication"),
+                        children: vec![],
+                        ..Link::default()
+                    },
+                    Link {
+                        path: String::from("/api/client"),
+                        source_path: Some(PathBuf::from("docs").join("api/client.md")),
+                        title: String::from("Client"),
+                        children: vec![],
+                        ..Link::default()
+                    },
+                ],
+                ..Link::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn manual_menu_recursive_glob() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started"), page("one.md", "One")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("api"),
+                docs: vec![
+                    page("api/README.md", "API"),
+                    page("api/client.md", "Client"),
+                ],
+                dirs: vec![Directory {
+                    path: PathBuf::from("docs").join("api").join("nested"),
+                    docs: vec![
+                        page("api/nested/README.md", "Nested"),
+                        page("api/nested/deep.md", "Deep"),
+                    ],
+                    dirs: vec![],
+                }],
+            }],
+        };
+
+        let rules = vec![NavRule::Glob(
+            PathBuf::from("docs").join("api").join("**").join("*.md"),
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        assert_eq!(
+            navigation
+                .customize(&rules, &links)
+                .unwrap()
+                .iter()
+                .map(|l| l.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/api/client", "/api/nested/deep", "/api/nested"]
+        );
+    }
+
+    #[test]
+    fn manual_menu_external_links() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started"), page("one.md", "One")],
+            dirs: vec![],
+        };
+
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+            NavRule::Link {
+                title: String::from("GitHub"),
+                url: String::from("https://github.com/Doctave/doctave"),
+                target: None,
+                classes: vec![],
+            },
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![
+                Link {
+                    path: String::from("/one"),
+                    source_path: Some(PathBuf::from("docs").join("one.md")),
+                    title: String::from("One"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("https://github.com/Doctave/doctave"),
+                    title: String::from("GitHub"),
+                    target: Some(String::from("_blank")),
+                    children: vec![],
+                    ..Link::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn manual_menu_collapsed_directory() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page("child/README.md", "Nested Root"),
+                    page("child/three.md", "Three"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let rules = vec![NavRule::Dir(
+            PathBuf::from("docs").join("child"),
+            Some(DirIncludeRule::WildCard),
+            DirOptions {
+                collapsed: true,
+                ..DirOptions::default()
+            },
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        assert!(links.iter().all(|l| l.expanded));
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![Link {
+                path: String::from("/child"),
+                source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                title: String::from("Nested Root"),
+                expanded: false,
+                children: vec![Link {
+                    path: String::from("/child/three"),
+                    source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                    title: String::from("Three"),
+                    children: vec![],
+                    ..Link::default()
+                }],
+                ..Link::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn flatten_in_reading_order() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        assert_eq!(
+            navigation
+                .flatten(&links)
+                .iter()
+                .map(|l| l.path.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "/child",
+                "/child/nested",
+                "/child/nested/four",
+                "/child/three",
+                "/one",
+                "/two",
+            ]
+        );
+    }
+
+    #[test]
+    fn neighbors() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        let (prev, next) = navigation.neighbors(&links, "/child/nested/four");
+        assert_eq!(prev.unwrap().path, "/child/nested");
+        assert_eq!(next.unwrap().path, "/child/three");
+
+        let (prev, next) = navigation.neighbors(&links, "/child");
+        assert_eq!(prev, None);
+        assert_eq!(next.unwrap().path, "/child/nested");
+
+        let (prev, next) = navigation.neighbors(&links, "/two");
+        assert_eq!(prev.unwrap().path, "/one");
+        assert_eq!(next, None);
+
+        assert_eq!(navigation.neighbors(&links, "/missing"), (None, None));
+    }
+
+    #[test]
+    fn neighbors_skip_external_links() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+            NavRule::Link {
+                title: String::from("GitHub"),
+                url: String::from("https://github.com/Doctave/doctave"),
+                target: None,
+                classes: vec![],
+            },
+            NavRule::File(PathBuf::from("docs").join("two.md"), LinkOptions::default()),
+        ];
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        let (prev, next) = navigation.neighbors(&links, "/one");
+        assert_eq!(prev, None);
+        assert_eq!(next.unwrap().path, "/two");
+    }
+
+    #[test]
+    fn breadcrumbs() {
+        let root = nested_root();
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+            NavRule::Dir(
+                PathBuf::from("docs").join("child"),
+                Some(DirIncludeRule::Explicit(vec![NavRule::Dir(
+                    PathBuf::from("docs").join("child").join("nested"),
+                    Some(DirIncludeRule::WildCard),
+                    DirOptions::default(),
+                )])),
+                DirOptions::default(),
+            ),
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(
+            navigation.breadcrumbs(&links, "/child/nested/four"),
+            vec![
+                Link {
+                    path: String::from("/child"),
+                    source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                    title: String::from("Nested Root"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child/nested"),
+                    source_path: Some(PathBuf::from("docs").join("child/nested/README.md")),
+                    title: String::from("Nested Root"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child/nested/four"),
+                    source_path: Some(PathBuf::from("docs").join("child/nested/four.md")),
+                    title: String::from("Four"),
+                    children: vec![],
+                    ..Link::default()
+                },
+            ]
+        );
+        assert_eq!(
+            navigation
+                .breadcrumbs(&links, "/one")
+                .iter()
+                .map(|l| l.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/one"]
+        );
+        assert_eq!(navigation.breadcrumbs(&links, "/child/three"), vec![]);
+    }
+
+    #[test]
+    fn path_to_uri_with_clean_urls() {
+        let config = config(None);
+
+        assert_eq!(Link::path_to_uri(Path::new("index.html"), &config), "/");
+        assert_eq!(Link::path_to_uri(Path::new("one.html"), &config), "/one");
+        assert_eq!(
+            Link::path_to_uri(Path::new("child/index.html"), &config),
+            "/child"
+        );
+        assert_eq!(
+            Link::path_to_uri(Path::new("child/three.html"), &config),
+            "/child/three"
+        );
+    }
+
+    #[test]
+    fn path_to_uri_without_clean_urls() {
+        let config = config(Some("---\ntitle: My project\nclean_urls: false\n"));
+
+        assert_eq!(Link::path_to_uri(Path::new("index.html"), &config), "/");
+        assert_eq!(
+            Link::path_to_uri(Path::new("one.html"), &config),
+            "/one.html"
+        );
+        assert_eq!(
+            Link::path_to_uri(Path::new("child/index.html"), &config),
+            "/child"
+        );
+        assert_eq!(
+            Link::path_to_uri(Path::new("child/three.html"), &config),
+            "/child/three.html"
+        );
+
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&nested_root());
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs").join("one.md"),
+            LinkOptions::default(),
+        )];
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![Link {
+                path: String::from("/one.html"),
+                source_path: Some(PathBuf::from("docs").join("one.md")),
+                title: String::from("One"),
+                children: vec![],
+                ..Link::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn path_to_uri_with_trailing_slash() {
+        let config = config(Some("---\ntitle: My project\ntrailing_slash: true\n"));
+
+        assert_eq!(Link::path_to_uri(Path::new("index.html"), &config), "/");
+        assert_eq!(Link::path_to_uri(Path::new("one.html"), &config), "/one");
+        assert_eq!(
+            Link::path_to_uri(Path::new("child/index.html"), &config),
+            "/child/"
+        );
+        assert_eq!(
+            Link::path_to_uri(Path::new("child/three.html"), &config),
+            "/child/three"
+        );
+    }
+
+    #[test]
+    fn manual_menu_with_trailing_slash() {
+        let config = config(Some("---\ntitle: My project\ntrailing_slash: true\n"));
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&nested_root());
+        let rules = vec![
+            NavRule::Dir(
+                PathBuf::from("docs").join("child"),
+                Some(DirIncludeRule::Explicit(vec![NavRule::Dir(
+                    PathBuf::from("docs").join("child").join("nested"),
+                    None,
+                    DirOptions::default(),
+                )])),
+                DirOptions::default(),
+            ),
+            NavRule::Glob(PathBuf::from("docs").join("child").join("*")),
+        ];
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![
+                Link {
+                    path: String::from("/child/"),
+                    source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                    title: String::from("Nested Root"),
+                    children: vec![Link {
+                        path: String::from("/child/nested/"),
+                        source_path: Some(PathBuf::from("docs").join("child/nested/README.md")),
+                        title: String::from("Nested Root"),
+                        children: vec![],
+                        ..Link::default()
+                    }],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child/nested/"),
+                    source_path: Some(PathBuf::from("docs").join("child/nested/README.md")),
+                    title: String::from("Nested Root"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child/three"),
+                    source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                    title: String::from("Three"),
+                    children: vec![],
+                    ..Link::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn hidden_pages() {
+        let config = config(None);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("one.md", "One"),
+                page_with("two.md", "Two", &[("hidden", "true")]),
+            ],
+            dirs: vec![
+                Directory {
+                    path: PathBuf::from("docs").join("child"),
+                    docs: vec![
+                        page_with("child/README.md", "Nested Root", &[("hidden", "true")]),
+                        page("child/three.md", "Three"),
+                    ],
+                    dirs: vec![],
+                },
+                Directory {
+                    path: PathBuf::from("docs").join("wip"),
+                    docs: vec![
+                        page_with("wip/README.md", "Work In Progress", &[("hidden", "true")]),
+                        page_with("wip/draft.md", "Draft", &[("hidden", "true")]),
+                    ],
+                    dirs: vec![],
+                },
+            ],
+        };
+
+        let navigation = Navigation::new(&config);
+
+        assert_eq!(
+            navigation.build_for(&root).unwrap(),
+            vec![
+                Link {
+                    path: String::from("/child"),
+                    source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                    title: String::from("Nested Root"),
+                    children: vec![Link {
+                        path: String::from("/child/three"),
+                        source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                        title: String::from("Three"),
+                        children: vec![],
+                        ..Link::default()
+                    }],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/one"),
+                    source_path: Some(PathBuf::from("docs").join("one.md")),
+                    title: String::from("One"),
+                    children: vec![],
+                    ..Link::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn manual_menu_mixed_case_and_backslashes() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&nested_root());
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs/ONE.md"), LinkOptions::default()),
+            NavRule::Dir(
+                PathBuf::from("docs\\Child"),
+                Some(DirIncludeRule::Explicit(vec![NavRule::File(
+                    PathBuf::from("docs\\child\\Three.md"),
+                    LinkOptions::default(),
+                )])),
+                DirOptions::default(),
+            ),
+        ];
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![
+                Link {
+                    path: String::from("/one"),
+                    source_path: Some(PathBuf::from("docs").join("one.md")),
+                    title: String::from("One"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child"),
+                    source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                    title: String::from("Nested Root"),
+                    children: vec![Link {
+                        path: String::from("/child/three"),
+                        source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                        title: String::from("Three"),
+                        children: vec![],
+                        ..Link::default()
+                    }],
+                    ..Link::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn to_json() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&navigation.to_json(&nested_root()).unwrap()).unwrap();
+
+        assert_eq!(json["links"][0]["path"], "/child");
+        assert_eq!(json["links"][0]["title"], "Nested Root");
+        assert_eq!(json["links"][0]["children"][0]["path"], "/child/nested");
+        assert_eq!(
+            json["links"][0]["children"][0]["children"][0]["path"],
+            "/child/nested/four"
+        );
+        assert_eq!(json["links"][0]["children"][1]["path"], "/child/three");
+        assert_eq!(json["links"][1]["path"], "/one");
+        assert_eq!(json["links"][1]["children"], serde_json::json!([]));
+        assert_eq!(json["links"][2]["path"], "/two");
+    }
+
+    #[test]
+    fn manual_menu_groups() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&nested_root());
+        let rules = vec![
+            NavRule::Group {
+                title: String::from("Guides"),
+                children: vec![
+                    NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+                    NavRule::File(PathBuf::from("docs").join("two.md"), LinkOptions::default()),
+                ],
+            },
+            NavRule::Group {
+                title: String::from("Reference"),
+                children: vec![NavRule::File(
+                    PathBuf::from("docs").join("child").join("three.md"),
+                    LinkOptions::default(),
+                )],
+            },
+        ];
+
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(
+            links,
+            vec![
+                Link {
+                    path: String::new(),
+                    title: String::from("Guides"),
+                    children: vec![
+                        Link {
+                            path: String::from("/one"),
+                            source_path: Some(PathBuf::from("docs").join("one.md")),
+                            title: String::from("One"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                        Link {
+                            path: String::from("/two"),
+                            source_path: Some(PathBuf::from("docs").join("two.md")),
+                            title: String::from("Two"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                    ],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::new(),
+                    title: String::from("Reference"),
+                    children: vec![Link {
+                        path: String::from("/child/three"),
+                        source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                        title: String::from("Three"),
+                        children: vec![],
+                        ..Link::default()
+                    }],
+                    ..Link::default()
+                },
+            ]
+        );
+
+        assert_eq!(
+            navigation
+                .flatten(&links)
+                .iter()
+                .map(|l| l.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/one", "/two", "/child/three"]
+        );
+
+        let (prev, next) = navigation.neighbors(&links, "/two");
+        assert_eq!(prev.unwrap().path, "/one");
+        assert_eq!(next.unwrap().path, "/child/three");
+
+        assert_eq!(
+            navigation
+                .breadcrumbs(&links, "/child/three")
+                .iter()
+                .map(|l| l.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/child/three"]
+        );
+    }
+
+    #[test]
+    fn nav_depth() {
+        let shallow = config(Some("---\ntitle: My project\nnav_depth: 1\n"));
+        let navigation = Navigation::new(&shallow);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        assert_eq!(
+            links.iter().map(|l| l.path.as_str()).collect::<Vec<_>>(),
+            vec!["/child", "/one", "/two"]
+        );
+        assert!(links.iter().all(|l| l.children.is_empty()));
+
+        let deeper = config(Some("---\ntitle: My project\nnav_depth: 2\n"));
+        let navigation = Navigation::new(&deeper);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        assert_eq!(
+            links[0]
+                .children
+                .iter()
+                .map(|l| l.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/child/nested", "/child/three"]
+        );
+        assert_eq!(links[0].children[0].children, vec![]);
+    }
+
+    #[test]
+    fn index_as_child() {
+        let config = config(Some(
+            "---\ntitle: My project\nindex_as_child: true\nindex_child_title: Introduction\n",
+        ));
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        assert_eq!(
+            links[0].children,
+            vec![
+                Link {
+                    path: String::from("/child"),
+                    source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                    title: String::from("Introduction"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child/nested"),
+                    source_path: Some(PathBuf::from("docs").join("child/nested/README.md")),
+                    title: String::from("Nested Root"),
+                    children: vec![
+                        Link {
+                            path: String::from("/child/nested"),
+                            source_path: Some(PathBuf::from("docs").join("child/nested/README.md")),
+                            title: String::from("Introduction"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                        Link {
+                            path: String::from("/child/nested/four"),
+                            source_path: Some(PathBuf::from("docs").join("child/nested/four.md")),
+                            title: String::from("Four"),
+                            children: vec![],
+                            ..Link::default()
+                        },
+                    ],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child/three"),
+                    source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                    title: String::from("Three"),
+                    children: vec![],
+                    ..Link::default()
+                },
+            ]
+        );
+
+        let (prev, next) = navigation.neighbors(&links, "/child");
+        assert_eq!(prev, None);
+        assert_eq!(next.unwrap().path, "/child/nested");
+    }
+
+    #[test]
+    fn sorting_reversed_directory() {
+        let config = config(None);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("a.md", "A"),
+                page("b.md", "B"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("changelog"),
+                docs: vec![
+                    page_with("changelog/README.md", "Changelog", &[("sort", "reverse")]),
+                    page("changelog/1.md", "0.1.0"),
+                    page("changelog/2.md", "0.2.0"),
+                    page("changelog/3.md", "0.10.0"),
+                ],
+                dirs: vec![Directory {
+                    path: PathBuf::from("docs").join("changelog").join("archive"),
+                    docs: vec![
+                        page("changelog/archive/README.md", "Archive"),
+                        page("changelog/archive/1.md", "Old"),
+                        page("changelog/archive/2.md", "Older"),
+                    ],
+                    dirs: vec![],
+                }],
+            }],
+        };
+
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        let titles = |links: &[Link]| links.iter().map(|l| l.title.clone()).collect::<Vec<_>>();
+
+        assert_eq!(titles(&links), vec!["A", "B", "Changelog"]);
+        assert_eq!(
+            titles(&links[2].children),
+            vec!["Archive", "0.10.0", "0.2.0", "0.1.0"]
+        );
+        assert_eq!(titles(&links[2].children[0].children), vec!["Old", "Older"]);
+    }
+
+    #[test]
+    fn manual_menu_duplicate_files() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&nested_root());
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+            NavRule::File(
+                PathBuf::from("docs").join("one.md"),
+                LinkOptions {
+                    title: Some(String::from("Again")),
+                    ..LinkOptions::default()
+                },
+            ),
+        ];
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![Link {
+                path: String::from("/one"),
+                source_path: Some(PathBuf::from("docs").join("one.md")),
+                title: String::from("One"),
+                children: vec![],
+                ..Link::default()
+            }]
+        );
+        assert_eq!(
+            navigation.take_warnings(),
+            vec![format!(
+                "Found {} more than once in the navigation rules. \
+                 Only the first one will be shown.",
+                PathBuf::from("docs").join("one.md").display()
+            )]
+        );
+        assert!(navigation.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn icons() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page_with("one.md", "One", &[("icon", "📖")]),
+                page("two.md", "Two"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page_with("child/README.md", "Nested Root", &[("icon", "🔧")]),
+                    page("child/three.md", "Three"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let links = navigation.build_for(&root).unwrap();
+        assert_eq!(
+            links.iter().map(|l| l.icon.as_deref()).collect::<Vec<_>>(),
+            vec![Some("🔧"), Some("📖"), None]
+        );
+
+        let rules = vec![
+            NavRule::File(
+                PathBuf::from("docs").join("two.md"),
+                LinkOptions {
+                    icon: Some(String::from("📦")),
+                    ..LinkOptions::default()
+                },
+            ),
+            NavRule::Dir(
+                PathBuf::from("docs").join("child"),
+                None,
+                DirOptions {
+                    link: LinkOptions {
+                        icon: Some(String::from("⚙️")),
+                        ..LinkOptions::default()
+                    },
+                    ..DirOptions::default()
+                },
+            ),
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+        ];
+
+        let links = navigation
+            .customize(&rules, &navigation.default_links(&root))
+            .unwrap();
+        assert_eq!(
+            links.iter().map(|l| l.icon.as_deref()).collect::<Vec<_>>(),
+            vec![Some("📦"), Some("⚙️"), Some("📖")]
+        );
+    }
+
+    #[test]
+    fn manual_menu_deep_wildcard() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&nested_root());
+        let rules = vec![NavRule::Dir(
+            PathBuf::from("docs").join("child"),
+            Some(DirIncludeRule::DeepWildCard),
+            DirOptions::default(),
+        )];
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap(),
+            vec![Link {
+                path: String::from("/child"),
+                source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                title: String::from("Nested Root"),
+                children: vec![
+                    Link {
+                        path: String::from("/child/nested/four"),
+                        source_path: Some(PathBuf::from("docs").join("child/nested/four.md")),
+                        title: String::from("Four"),
+                        children: vec![],
+                        ..Link::default()
+                    },
+                    Link {
+                        path: String::from("/child/three"),
+                        source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                        title: String::from("Three"),
+                        children: vec![],
+                        ..Link::default()
+                    },
+                ],
+                ..Link::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn mark_active() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let mut links = navigation.build_for(&nested_root()).unwrap();
+
+        navigation.mark_active(&mut links, "/child/nested/four", None);
+
+        let child = &links[0];
+        let nested = &child.children[0];
+        let four = &nested.children[0];
+
+        assert_eq!((child.active, child.active_trail), (false, true));
+        assert_eq!((nested.active, nested.active_trail), (false, true));
+        assert_eq!((four.active, four.active_trail), (true, false));
+        assert!(navigation
+            .flatten(&links)
+            .iter()
+            .filter(|l| l.path != "/child" && l.path != "/child/nested")
+            .filter(|l| l.path != "/child/nested/four")
+            .all(|l| !l.active && !l.active_trail));
+
+        let mut links = navigation.build_for(&nested_root()).unwrap();
+        navigation.mark_active(&mut links, "/missing", None);
+
+        assert!(navigation
+            .flatten(&links)
+            .iter()
+            .all(|l| !l.active && !l.active_trail));
+    }
+
+    #[test]
+    fn slugify() {
+        assert_eq!(Link::slugify("Déjà Vu & Friends"), "deja-vu-friends");
+        assert_eq!(Link::slugify("Rock & Roll"), "rock-roll");
+        assert_eq!(Link::slugify("  Lots   of    spaces  "), "lots-of-spaces");
+        assert_eq!(Link::slugify("Straße über Ørsted"), "strasse-uber-orsted");
+        assert_eq!(Link::slugify("--Already-a-slug--"), "already-a-slug");
+        assert_eq!(Link::slugify("Version 2.0"), "version-2-0");
+        assert_eq!(Link::slugify("日本語"), "");
+    }
+
+    #[test]
+    fn slugs_from_titles() {
+        let mut titled = page("001-intro.md", "Déjà Vu & Friends");
+        titled.slugify_title();
+
+        let mut slugged = page_with("002-setup.md", "Setting Up", &[("slug", "setup")]);
+        slugged.slugify_title();
+
+        let mut index = page("README.md", "Getting Started");
+        index.slugify_title();
+
+        let config = config(None);
+        assert_eq!(titled.uri_path(&config), "/deja-vu-friends");
+        assert_eq!(slugged.uri_path(&config), "/setup");
+        assert_eq!(index.uri_path(&config), "/");
+    }
+
+    #[test]
+    fn manual_menu_slugged_page() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page_with("001-intro.md", "Intro", &[("slug", "intro")]),
+            ],
+            dirs: vec![],
+        };
+
+        let links = navigation.default_links(&root);
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs").join("001-intro.md"),
+            LinkOptions::default(),
+        )];
+
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].path, "/intro");
+        assert_eq!(links[0].title, "Intro");
+    }
+
+    #[test]
+    fn sorting_by_date() {
+        let config = config(None);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("blog"),
+                docs: vec![
+                    page_with("blog/README.md", "Blog", &[("sort", "date")]),
+                    page_with("blog/a.md", "Launch", &[("date", "2020-03-01")]),
+                    page("blog/b.md", "Drafts"),
+                    page_with("blog/c.md", "Roadmap", &[("date", "2020-11-15")]),
+                    page_with("blog/d.md", "Hello", &[("date", "2019-12-31T10:00:00Z")]),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        let titles = links[0]
+            .children
+            .iter()
+            .map(|l| l.title.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(titles, vec!["Roadmap", "Launch", "Hello", "Drafts"]);
+    }
+
+    #[test]
+    fn find() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        let one = navigation.find(&links, "/one").unwrap();
+        assert_eq!(one.title, "One");
+
+        let four = navigation.find(&links, "/child/nested/four").unwrap();
+        assert_eq!(four.title, "Four");
+
+        let nested = navigation.find(&links, "/child/nested/").unwrap();
+        assert_eq!(nested.path, "/child/nested");
+        assert_eq!(nested.children.len(), 1);
+
+        assert_eq!(navigation.find(&links, "/child/five"), None);
+    }
+
+    #[test]
+    fn directory_without_index() {
+        let config = config(None);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("getting-started-guides"),
+                docs: vec![
+                    page("getting-started-guides/b.md", "Second"),
+                    page("getting-started-guides/a.md", "First"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].title, "Getting Started Guides");
+        assert_eq!(links[0].path, "/getting-started-guides/a");
+        assert_eq!(links[0].children.len(), 2);
+        assert_eq!(links[0].children[0].title, "First");
+
+        let warnings = navigation.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("getting-started-guides does not have a README.md"));
+    }
+
+    #[test]
+    fn manual_menu_explicit_order_is_preserved() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("guides"),
+                docs: vec![
+                    page("guides/README.md", "Guides"),
+                    page("guides/alpha.md", "Alpha"),
+                    page_with("guides/beta.md", "Beta", &[("order", "1")]),
+                    page("guides/gamma.md", "Gamma"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let guide = |name: &str| {
+            NavRule::File(
+                PathBuf::from("docs").join("guides").join(name),
+                LinkOptions::default(),
+            )
+        };
+
+        let rules = vec![NavRule::Dir(
+            PathBuf::from("docs").join("guides"),
+            Some(DirIncludeRule::Explicit(vec![
+                guide("gamma.md"),
+                guide("beta.md"),
+                guide("alpha.md"),
+            ])),
+            DirOptions::default(),
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        let titles = links[0]
+            .children
+            .iter()
+            .map(|l| l.title.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(titles, vec!["Gamma", "Beta", "Alpha"]);
+    }
+
+    #[test]
+    fn manual_menu_directory_title() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("reference"),
+                docs: vec![
+                    page("reference/README.md", "Reference"),
+                    page("reference/nested.md", "Nested"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        let options = DirOptions {
+            link: LinkOptions {
+                title: Some(String::from("API Reference")),
+                ..LinkOptions::default()
+            },
+            ..DirOptions::default()
+        };
+        let reference = PathBuf::from("docs").join("reference");
+        let nested = reference.join("nested.md");
+
+        for dir_rule in vec![
+            None,
+            Some(DirIncludeRule::WildCard),
+            Some(DirIncludeRule::Explicit(vec![NavRule::File(
+                nested.clone(),
+                LinkOptions::default(),
+            )])),
+        ] {
+            let rules = vec![NavRule::Dir(reference.clone(), dir_rule, options.clone())];
+            let customized = navigation.customize(&rules, &links).unwrap();
+
+            assert_eq!(customized[0].title, "API Reference");
+            assert!(customized[0].children.iter().all(|c| c.title == "Nested"));
+        }
+
+        assert_eq!(root.dirs[0].index().unwrap().title(), "Reference");
+    }
+
+    #[test]
+    fn large_tree_is_built_deterministically() {
+        fn tree(path: PathBuf, depth: usize) -> Directory {
+            let prefix = path.strip_prefix("docs").unwrap().to_path_buf();
+            let file = |name: &str| prefix.join(name).to_string_lossy().into_owned();
+
+            let mut docs = vec![page(&file("README.md"), &format!("Index {}", depth))];
+            for i in (0..20).rev() {
+                docs.push(page(
+                    &file(&format!("page-{}.md", i)),
+                    &format!("Page {}", i),
+                ));
+            }
+
+            let dirs = if depth == 0 {
+                vec![]
+            } else {
+                (0..8)
+                    .rev()
+                    .map(|i| tree(path.join(format!("dir-{}", i)), depth - 1))
+                    .collect()
+            };
+
+            Directory { path, docs, dirs }
+        }
+
+        fn assert_sorted(links: &[Link]) {
+            for pair in links.windows(2) {
+                assert_ne!(Link::compare(&pair[0], &pair[1]), Ordering::Greater);
+            }
+            for link in links {
+                assert_sorted(&link.children);
+            }
+        }
+
+        let root = tree(PathBuf::from("docs"), 2);
+        let config = config(None);
+
+        // With a single thread the directories are built one after another
+        let sequential = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap()
+            .install(|| Navigation::new(&config).build_for(&root).unwrap());
+
+        assert_sorted(&sequential);
+        assert_eq!(
+            Navigation::new(&config).flatten(&sequential).len(),
+            20 + 8 * (21 + 8 * 21)
+        );
+
+        // A new navigation each time, so that nothing is served from the cache
+        for _ in 0..5 {
+            let navigation = Navigation::new(&config);
+            assert_eq!(navigation.build_for(&root).unwrap(), sequential);
+        }
+    }
+
+    #[test]
+    fn dom_id() {
+        let link = |path: &str| Link {
+            path: String::from(path),
+            title: String::from("A Title"),
+            ..Link::default()
+        };
+
+        assert_eq!(link("/").dom_id(), "nav-root");
+        assert_eq!(
+            link("/child/nested/four").dom_id(),
+            "nav--child--nested--four"
+        );
+        assert_eq!(link("/child/nested/").dom_id(), "nav--child--nested");
+        assert_eq!(link("/tutorial.html").dom_id(), "nav--tutorial_2e_html");
+        assert_eq!(
+            link("https://github.com/Doctave/doctave").dom_id(),
+            "nav--https_3a_----github_2e_com--Doctave--doctave"
+        );
+        assert_eq!(link("").dom_id(), "nav-group--A_20_Title");
+
+        assert_eq!(link("/one").dom_id(), link("/one").dom_id());
+        assert_ne!(link("/one").dom_id(), link("/two").dom_id());
+    }
+
+    #[test]
+    fn dom_id_collisions() {
+        let paths = [
+            "/a/b-c", "/a/b/c", "/a-b/c", "/a_b", "/a-b", "/a--b", "/a-/b", "/a/-b", "/a//b",
+            "/a b", "/A-b", "/a.b", "/a_2e_b", "/root", "/",
+        ];
+
+        let ids = paths
+            .iter()
+            .map(|path| {
+                Link {
+                    path: String::from(*path),
+                    ..Link::default()
+                }
+                .dom_id()
+            })
+            .collect::<BTreeSet<_>>();
+
+        assert_eq!(ids.len(), paths.len());
+        assert_eq!(
+            Link {
+                path: String::from("/a/b-c"),
+                ..Link::default()
+            }
+            .dom_id(),
+            "nav--a--b-c"
+        );
+    }
+
+    #[test]
+    fn named_menus() {
+        let yaml = indoc! {"
+            ---
+            title: My project
+            navigation:
+              guides:
+                - path: docs/tutorial.md
+                - path: docs/features
+                  children: \"*\"
+              api:
+                - path: docs/configuration.md
+        "};
+        let config = Config::from_yaml_str(Path::new(""), yaml).unwrap();
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("tutorial.md", "Tutorial"),
+                page("configuration.md", "Configuration"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("features"),
+                docs: vec![
+                    page("features/README.md", "Features"),
+                    page("features/markdown.md", "Markdown"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let navigation = Navigation::new(&config);
+        let guides = navigation.build_named("guides", &root).unwrap();
+        let api = navigation.build_named("api", &root).unwrap();
+
+        let titles = |links: &[Link]| links.iter().map(|l| l.title.clone()).collect::<Vec<_>>();
+
+        assert_eq!(titles(&guides), vec!["Tutorial", "Features"]);
+        assert_eq!(titles(&guides[1].children), vec!["Markdown"]);
+        assert_eq!(titles(&api), vec!["Configuration"]);
+
+        let error = navigation.build_named("blog", &root).unwrap_err();
+        assert_eq!(
+            format!("{}", error),
+            "Could not find a navigation named blog in doctave.yaml.\n\
+             The available navigations are: api, guides"
+        );
+    }
+
+    #[test]
+    fn named_menus_default_sidebar() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("tutorial.md", "Tutorial"),
+                page("configuration.md", "Configuration"),
+            ],
+            dirs: vec![],
+        };
+        let titles = |links: &[Link]| links.iter().map(|l| l.title.clone()).collect::<Vec<_>>();
+
+        // Without a menu called default, the first one is shown
+        let config = Config::from_yaml_str(
+            Path::new(""),
+            indoc! {"
+                ---
+                title: My project
+                navigation:
+                  guides:
+                    - path: docs/tutorial.md
+                  api:
+                    - path: docs/configuration.md
+            "},
+        )
+        .unwrap();
+
+        assert_eq!(config.default_navigation_name(), Some("guides"));
+        let links = Navigation::new(&config).build_for(&root).unwrap();
+        assert_eq!(titles(&links), vec!["Tutorial"]);
+
+        let config = Config::from_yaml_str(
+            Path::new(""),
+            indoc! {"
+                ---
+                title: My project
+                navigation:
+                  default:
+                    - path: docs/tutorial.md
+                  api:
+                    - path: docs/configuration.md
+            "},
+        )
+        .unwrap();
+
+        assert_eq!(config.default_navigation_name(), Some("default"));
+        let links = Navigation::new(&config).build_for(&root).unwrap();
+        assert_eq!(titles(&links), vec!["Tutorial"]);
+    }
+
+    #[test]
+    fn manual_menu_slugged_pages_in_directory() {
+        let mut titled = page("guides/001-first-steps.md", "First Steps");
+        titled.slugify_title();
+
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("guides"),
+                docs: vec![
+                    page("guides/README.md", "Guides"),
+                    titled,
+                    page_with("guides/002-setup.md", "Setup", &[("slug", "setup")]),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let guides = PathBuf::from("docs").join("guides");
+        let rules = vec![NavRule::Dir(
+            guides.clone(),
+            Some(DirIncludeRule::Explicit(vec![
+                NavRule::File(guides.join("002-setup.md"), LinkOptions::default()),
+                NavRule::File(guides.join("001-first-steps.md"), LinkOptions::default()),
+            ])),
+            DirOptions::default(),
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        let paths = links[0]
+            .children
+            .iter()
+            .map(|l| l.path.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(paths, vec!["/guides/setup", "/guides/first-steps"]);
+    }
+
+    #[test]
+    fn all_paths() {
+        let root = nested_root();
+        let rules = vec![
+            NavRule::Group {
+                title: String::from("Start here"),
+                children: vec![NavRule::File(
+                    PathBuf::from("docs").join("one.md"),
+                    LinkOptions::default(),
+                )],
+            },
+            NavRule::Link {
+                title: String::from("GitHub"),
+                url: String::from("https://github.com/Doctave/doctave"),
+                target: None,
+                classes: vec![],
+            },
+            NavRule::Dir(
+                PathBuf::from("docs").join("child"),
+                Some(DirIncludeRule::WildCard),
+                DirOptions::default(),
+            ),
+            NavRule::Dir(
+                PathBuf::from("docs").join("child").join("nested"),
+                Some(DirIncludeRule::WildCard),
+                DirOptions::default(),
+            ),
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(
+            navigation.all_paths(&links),
+            vec![
+                "/one",
+                "/child",
+                "/child/nested",
+                "/child/nested/four",
+                "/child/three"
+            ]
+        );
+    }
+
+    #[test]
+    fn nav_exclude() {
+        let config = config(Some(indoc! {r#"
+            ---
+            title: My project
+            nav_exclude:
+              - "**/_*.md"
+              - "drafts/**"
+        "#}));
+
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("one.md", "One"),
+                page("_snippet.md", "Snippet"),
+            ],
+            dirs: vec![
+                Directory {
+                    path: PathBuf::from("docs").join("child"),
+                    docs: vec![
+                        page("child/README.md", "Child"),
+                        page("child/two.md", "Two"),
+                        page("child/_partial.md", "Partial"),
+                    ],
+                    dirs: vec![],
+                },
+                Directory {
+                    path: PathBuf::from("docs").join("drafts"),
+                    docs: vec![
+                        page("drafts/README.md", "Drafts"),
+                        page("drafts/unfinished.md", "Unfinished"),
+                    ],
+                    dirs: vec![],
+                },
+            ],
+        };
+
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        let titles = |links: &[Link]| links.iter().map(|l| l.title.clone()).collect::<Vec<_>>();
+
+        assert_eq!(titles(&links), vec!["Child", "One"]);
+        assert_eq!(titles(&links[0].children), vec!["Two"]);
+    }
+
+    #[test]
+    fn title_fallbacks() {
+        let document = |path: &str, raw: &str, title: Option<&str>| {
+            let mut frontmatter = BTreeMap::new();
+            if let Some(title) = title {
+                frontmatter.insert("title".to_string(), title.to_string());
+            }
+
+            Document::new(Path::new(path), raw.to_string(), frontmatter)
+        };
+
+        let titled = document("intro.md", "# Heading", Some("From Frontmatter"));
+        assert_eq!(titled.title(), "From Frontmatter");
+
+        let heading_only = document("intro.md", "Some text\n\n  # Welcome to Doctave #\n", None);
+        assert_eq!(heading_only.title(), "Welcome to Doctave");
+
+        let code_first = document(
+            "intro.md",
+            "```bash\n# not a heading\n```\n\n## Second level\n\n# Real Heading",
+            None,
+        );
+        assert_eq!(code_first.title(), "Real Heading");
+
+        let no_heading = document("getting-started_guide.md", "No headings here", None);
+        assert_eq!(no_heading.title(), "Getting started guide");
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started"), heading_only],
+            dirs: vec![],
+        };
+
+        let links = navigation.default_links(&root);
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs").join("intro.md"),
+            LinkOptions {
+                title: Some(String::from("From Rule")),
+                ..LinkOptions::default()
+            },
+        )];
+
+        assert_eq!(links[0].title, "Welcome to Doctave");
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap()[0].title,
+            "From Rule"
+        );
+    }
+
+    #[test]
+    fn sorting_descending() {
+        let config = config(Some(indoc! {"
+            ---
+            title: My project
+            sort_direction: desc
+        "}));
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("one.md", "One"),
+                page("two.md", "Two"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page("child/README.md", "Nested Root"),
+                    page("child/three.md", "Three"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let navigation = Navigation::new(&config);
+
+        assert_eq!(
+            navigation.build_for(&root).unwrap(),
+            vec![
+                Link {
+                    path: String::from("/two"),
+                    source_path: Some(PathBuf::from("docs").join("two.md")),
+                    title: String::from("Two"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/one"),
+                    source_path: Some(PathBuf::from("docs").join("one.md")),
+                    title: String::from("One"),
+                    children: vec![],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/child"),
+                    source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                    title: String::from("Nested Root"),
+                    children: vec![Link {
+                        path: String::from("/child/three"),
+                        source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                        title: String::from("Three"),
+                        children: vec![],
+                        ..Link::default()
+                    }],
+                    ..Link::default()
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn manual_menu_divider() {
+        let root = nested_root();
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+            NavRule::Divider,
+            NavRule::File(PathBuf::from("docs").join("two.md"), LinkOptions::default()),
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(links.len(), 3);
+        assert!(links[1].divider);
+        assert!(!links[1].is_group());
+        assert_eq!(links[1].path, "");
+        assert_eq!(links[1].title, "");
+
+        let paths = |links: Vec<Link>| links.into_iter().map(|l| l.path).collect::<Vec<_>>();
+
+        assert_eq!(paths(navigation.flatten(&links)), vec!["/one", "/two"]);
+        assert_eq!(navigation.all_paths(&links), vec!["/one", "/two"]);
+        assert_eq!(navigation.breadcrumbs(&links, "/two").len(), 1);
+        assert_eq!(navigation.find(&links, "/"), None);
+
+        let (previous, next) = navigation.neighbors(&links, "/one");
+        assert_eq!(previous, None);
+        assert_eq!(next.map(|l| l.path), Some(String::from("/two")));
+    }
+
+    #[test]
+    fn unreferenced() {
+        let mut root = nested_root();
+        root.docs
+            .push(page_with("secret.md", "Secret", &[("hidden", "true")]));
+
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+            NavRule::Link {
+                title: String::from("GitHub"),
+                url: String::from("https://github.com/Doctave/doctave"),
+                target: None,
+                classes: vec![],
+            },
+            NavRule::Dir(
+                PathBuf::from("docs").join("child"),
+                Some(DirIncludeRule::Explicit(vec![NavRule::Dir(
+                    PathBuf::from("docs").join("child").join("nested"),
+                    Some(DirIncludeRule::WildCard),
+                    DirOptions::default(),
+                )])),
+                DirOptions::default(),
+            ),
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(
+            navigation.unreferenced(&root, &links),
+            vec![
+                PathBuf::from("docs").join("two.md"),
+                PathBuf::from("docs").join("child").join("three.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn title_case() {
+        assert_eq!(Link::title_case("getting-started"), "Getting Started");
+        assert_eq!(Link::title_case("API reference"), "API Reference");
+        assert_eq!(Link::title_case("HTTP-API"), "HTTP API");
+        assert_eq!(
+            Link::title_case("the lord OF the rings and more"),
+            "The Lord of the Rings and More"
+        );
+        assert_eq!(Link::title_case("  a   tale  "), "A Tale");
+    }
+
+    #[test]
+    fn title_case_option() {
+        let config = config(Some(indoc! {"
+            ---
+            title: My project
+            title_case: true
+        "}));
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("one.md", "installing-the-CLI"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![page("child/README.md", "API reference")],
+                dirs: vec![],
+            }],
+        };
+
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+        assert_eq!(links[0].title, "API Reference");
+        assert_eq!(links[1].title, "Installing the CLI");
+
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs").join("one.md"),
+            LinkOptions {
+                title: Some(String::from("install the cli")),
+                ..LinkOptions::default()
+            },
+        )];
+        let links = navigation
+            .customize(&rules, &navigation.default_links(&root))
+            .unwrap();
+        assert_eq!(links[0].title, "install the cli");
+    }
+
+    #[test]
+    fn parent_of() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        assert_eq!(navigation.parent_of(&links, "/one"), None);
+        assert_eq!(navigation.parent_of(&links, "/child"), None);
+        assert_eq!(navigation.parent_of(&links, "/missing"), None);
+
+        let parent = navigation.parent_of(&links, "/child/three").unwrap();
+        assert_eq!(parent.path, "/child");
+
+        let parent = navigation.parent_of(&links, "/child/nested/four").unwrap();
+        assert_eq!(parent.path, "/child/nested");
+    }
+
+    #[test]
+    fn manual_menu_file_rule_for_directory_index() {
+        let root = nested_root();
+        let rules = vec![
+            NavRule::File(
+                PathBuf::from("docs").join("child").join("README.md"),
+                LinkOptions {
+                    title: Some(String::from("Child Section")),
+                    ..LinkOptions::default()
+                },
+            ),
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].path, "/child");
+        assert_eq!(links[0].title, "Child Section");
+        assert_eq!(
+            links[0]
+                .children
+                .iter()
+                .map(|l| l.path.clone())
+                .collect::<Vec<_>>(),
+            vec!["/child/nested", "/child/three"]
+        );
+        assert_eq!(links[0].children[0].children.len(), 1);
+    }
+
+    #[test]
+    fn annotate_depth() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let mut links = navigation.build_for(&nested_root()).unwrap();
+
+        navigation.annotate_depth(&mut links);
+
+        let depth = |path: &str| navigation.find(&links, path).unwrap().depth;
+
+        assert_eq!(depth("/one"), 0);
+        assert_eq!(depth("/child"), 0);
+        assert_eq!(depth("/child/three"), 1);
+        assert_eq!(depth("/child/nested"), 1);
+        assert_eq!(depth("/child/nested/four"), 2);
+    }
+
+    #[test]
+    fn merge_dirs() {
+        let guides = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("tutorial.md", "Tutorial"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("howto"),
+                docs: vec![
+                    page("howto/README.md", "How-To"),
+                    page("howto/deploy.md", "Deploy"),
+                ],
+                dirs: vec![],
+            }],
+        };
+        let api = Directory {
+            path: PathBuf::from("api"),
+            docs: vec![page("auth.md", "Authentication"), page("users.md", "Users")],
+            dirs: vec![],
+        };
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation
+            .build_for(&navigation.merge_dirs(&[&guides, &api]).unwrap())
+            .unwrap();
+
+        assert_eq!(
+            links.iter().map(|l| l.path.clone()).collect::<Vec<_>>(),
+            vec!["/auth", "/howto", "/tutorial", "/users"]
+        );
+        assert_eq!(links[1].children.len(), 1);
+
+        assert_eq!(
+            navigation.merge_dirs(&[&guides, &guides]).err(),
+            Some(NavigationError::Collision {
+                path: String::from("/tutorial")
+            })
+        );
+    }
+
+    #[test]
+    fn truncate_title() {
+        let mut link = Link {
+            path: String::from("/deploy"),
+            title: String::from("Deploying to production"),
+            ..Link::default()
+        };
+        link.truncate_title(9);
+        assert_eq!(link.title, "Deploying…");
+        assert_eq!(link.title_full.as_deref(), Some("Deploying to production"));
+
+        let mut multibyte = Link {
+            path: String::from("/emoji"),
+            title: String::from("Ünïcödé 🦀🦀🦀 títlé"),
+            ..Link::default()
+        };
+        multibyte.truncate_title(9);
+        assert_eq!(multibyte.title, "Ünïcödé 🦀…");
+
+        let mut short = Link {
+            path: String::from("/short"),
+            title: String::from("Shört"),
+            ..Link::default()
+        };
+        short.truncate_title(5);
+        assert_eq!(short.title, "Shört");
+        assert_eq!(short.title_full, None);
+    }
+
+    #[test]
+    fn truncate_titles() {
+        let config = config(Some(indoc! {"
+            ---
+            title: My project
+            nav_title_max_len: 4
+        "}));
+        let navigation = Navigation::new(&config);
+        let mut links = navigation.build_for(&nested_root()).unwrap();
+
+        navigation.truncate_titles(&mut links);
+
+        assert_eq!(links[0].title, "Nest…");
+        assert_eq!(links[0].children[1].title, "Thre…");
+        assert_eq!(links[1].title, "One");
+    }
+
+    #[test]
+    fn nav_title() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page_with(
+                    "install.md",
+                    "Installing Doctave on your machine",
+                    &[("nav_title", "Install")],
+                ),
+            ],
+            dirs: vec![],
+        };
+
+        let links = navigation.build_for(&root).unwrap();
+
+        assert_eq!(links[0].title, "Install");
+        assert_eq!(root.docs[1].title(), "Installing Doctave on your machine");
+    }
+
+    #[test]
+    fn manual_menu_cycle() {
+        let root = nested_root();
+        let child = PathBuf::from("docs").join("child");
+        let nested = child.join("nested");
+
+        let rules = vec![NavRule::Dir(
+            child.clone(),
+            Some(DirIncludeRule::Explicit(vec![NavRule::Dir(
+                nested.clone(),
+                Some(DirIncludeRule::Explicit(vec![NavRule::Dir(
+                    child.clone(),
+                    Some(DirIncludeRule::WildCard),
+                    DirOptions::default(),
+                )])),
+                DirOptions::default(),
+            )])),
+            DirOptions::default(),
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let error = navigation.customize(&rules, &links).unwrap_err();
+
+        assert_eq!(
+            error,
+            NavigationError::Cycle {
+                chain: vec![child.clone(), nested, child.clone()]
+            }
+        );
+        assert_eq!(
+            format!("{}", error),
+            "Found a directory nested under itself in the navigation rules: \
+             docs/child -> docs/child/nested -> docs/child"
+        );
+
+        // Listing pages from inside the directory itself is fine
+        let siblings = vec![NavRule::Dir(
+            child.clone(),
+            Some(DirIncludeRule::Explicit(vec![NavRule::File(
+                child.join("three.md"),
+                LinkOptions::default(),
+            )])),
+            DirOptions::default(),
+        )];
+        assert!(navigation.customize(&siblings, &links).is_ok());
+    }
+
+    #[test]
+    fn collapse_single() {
+        let config = config(Some(indoc! {"
+            ---
+            title: My project
+            collapse_single: true
+        "}));
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started"), page("one.md", "One")],
+            dirs: vec![
+                Directory {
+                    path: PathBuf::from("docs").join("single"),
+                    docs: vec![
+                        page("single/README.md", "Single"),
+                        page("single/only.md", "Only Page"),
+                    ],
+                    dirs: vec![],
+                },
+                Directory {
+                    path: PathBuf::from("docs").join("multiple"),
+                    docs: vec![
+                        page("multiple/README.md", "Multiple"),
+                        page("multiple/first.md", "First"),
+                        page("multiple/second.md", "Second"),
+                    ],
+                    dirs: vec![],
+                },
+            ],
+        };
+
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        let titles = |links: &[Link]| links.iter().map(|l| l.title.clone()).collect::<Vec<_>>();
+
+        assert_eq!(titles(&links), vec!["Multiple", "One", "Only Page"]);
+        assert_eq!(links[2].path, "/single/only");
+        assert!(links[2].children.is_empty());
+        assert_eq!(titles(&links[0].children), vec!["First", "Second"]);
+    }
+
+    #[test]
+    fn show_home() {
+        let config = config(Some(
+            "---\ntitle: My project\nshow_home: true\nhome_title: Start\n",
+        ));
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        assert_eq!(
+            links[0],
+            Link {
+                path: String::from("/"),
+                source_path: Some(PathBuf::from("docs").join("README.md")),
+                title: String::from("Start"),
+                children: vec![],
+                ..Link::default()
+            }
+        );
+        assert!(links[1..].iter().all(|l| l.path != "/"));
+        assert!(links[1].children.iter().all(|l| l.path != "/"));
+    }
+
+    #[test]
+    fn no_home_by_default() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        assert!(links.iter().all(|l| l.path != "/"));
+    }
+
+    #[test]
+    fn manual_menu_wildcard_except() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started"), page("one.md", "One")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page("child/README.md", "Nested Root"),
+                    page("child/draft.md", "Draft"),
+                    page("child/three.md", "Three"),
+                    page("child/four.md", "Four"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let rules = vec![NavRule::Dir(
+            PathBuf::from("docs/child"),
+            Some(DirIncludeRule::Except(vec![PathBuf::from(
+                "docs/child/draft.md",
+            )])),
+            DirOptions::default(),
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(
+            links[0]
+                .children
+                .iter()
+                .map(|l| l.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/child/four", "/child/three"]
+        );
+    }
+
+    #[test]
+    fn page_count() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let mut links = navigation.build_for(&nested_root()).unwrap();
+
+        assert_eq!(navigation.page_count(&links), 6);
+
+        links.push(Link {
+            divider: true,
+            ..Link::default()
+        });
+        links.push(Link {
+            path: String::from("https://github.com/Doctave/doctave"),
+            title: String::from("GitHub"),
+            ..Link::default()
+        });
+        links.push(Link {
+            title: String::from("Group"),
+            children: vec![links[0].clone()],
+            ..Link::default()
+        });
+
+        assert_eq!(navigation.page_count(&links), 6);
+    }
+
+    #[test]
+    fn resolve_relative_same_directory() {
+        let config = config(None);
+
+        assert_eq!(
+            Link::resolve_relative(Path::new("child/two.md"), "./three.md", &config),
+            "/child/three"
+        );
+        assert_eq!(
+            Link::resolve_relative(Path::new("child/two.md"), "three.md#usage", &config),
+            "/child/three#usage"
+        );
+    }
+
+    #[test]
+    fn resolve_relative_parent_directory() {
+        let config = config(None);
+
+        assert_eq!(
+            Link::resolve_relative(Path::new("child/nested/four.md"), "../three.md", &config),
+            "/child/three"
+        );
+        assert_eq!(
+            Link::resolve_relative(Path::new("child/three.md"), "../one.md", &config),
+            "/one"
+        );
+    }
+
+    #[test]
+    fn resolve_relative_index() {
+        let config = config(None);
+
+        assert_eq!(
+            Link::resolve_relative(Path::new("child/three.md"), "nested/README.md", &config),
+            "/child/nested"
+        );
+        assert_eq!(
+            Link::resolve_relative(Path::new("child/three.md"), "../README.md", &config),
+            "/"
+        );
+    }
+
+    #[test]
+    fn manual_menu_dir_overview() {
+        let root = nested_root();
+        let rules = vec![
+            NavRule::Dir(
+                PathBuf::from("docs/child"),
+                Some(DirIncludeRule::WildCard),
+                DirOptions {
+                    overview: Some(String::from("Overview")),
+                    ..DirOptions::default()
+                },
+            ),
+            NavRule::Dir(
+                PathBuf::from("docs/child/nested"),
+                Some(DirIncludeRule::WildCard),
+                DirOptions::default(),
+            ),
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(
+            links[0].children[0],
+            Link {
+                path: String::from("/child"),
+                source_path: Some(PathBuf::from("docs").join("child/README.md")),
+                title: String::from("Overview"),
+                children: vec![],
+                ..Link::default()
+            }
+        );
+        assert_eq!(links[0].children.len(), 3);
+        assert!(links[1].children.iter().all(|l| l.path != "/child/nested"));
+    }
+
+    fn sort_fixture() -> Vec<Link> {
+        let link = |title: &str, order: Option<i64>| Link {
+            path: format!("/{}", title.to_lowercase()),
+            title: title.to_owned(),
+            order,
+            ..Link::default()
+        };
+
+        vec![
+            link("Beta", None),
+            link("Page 10", None),
+            link("Last", Some(2)),
+            link("Page 2", None),
+            link("First", Some(1)),
+        ]
+    }
+
+    fn sorted_titles(mode: SortMode) -> Vec<String> {
+        let mut links = sort_fixture();
+        sort_links(&mut links, mode);
+
+        links.into_iter().map(|l| l.title).collect()
+    }
+
+    #[test]
+    fn sort_links_alphanumeric() {
+        assert_eq!(
+            sorted_titles(SortMode::Alphanumeric),
+            vec!["First", "Last", "Beta", "Page 2", "Page 10"]
+        );
+    }
+
+    #[test]
+    fn sort_links_alphanumeric_desc() {
+        assert_eq!(
+            sorted_titles(SortMode::AlphanumericDesc),
+            vec!["First", "Last", "Page 10", "Page 2", "Beta"]
+        );
+    }
+
+    #[test]
+    fn sort_links_order() {
+        assert_eq!(
+            sorted_titles(SortMode::Order),
+            vec!["First", "Last", "Beta", "Page 10", "Page 2"]
+        );
+    }
+
+    #[test]
+    fn sort_links_as_declared() {
+        assert_eq!(
+            sorted_titles(SortMode::AsDeclared),
+            vec!["Beta", "Page 10", "Last", "Page 2", "First"]
+        );
+    }
+
+    #[test]
+    fn sort_links_reverse() {
+        assert_eq!(
+            sorted_titles(SortMode::Reverse),
+            vec!["Page 10", "Page 2", "Beta", "Last", "First"]
+        );
+    }
+
+    fn languages_root() -> Directory {
+        let language = |name: &str, guide: &str| Directory {
+            path: PathBuf::from("docs").join(name),
+            docs: vec![
+                page(&format!("{}/README.md", name), "Start"),
+                page(&format!("{}/guide.md", name), guide),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join(name).join("api"),
+                docs: vec![
+                    page(&format!("{}/api/README.md", name), "API"),
+                    page(&format!("{}/api/auth.md", name), "Auth"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Languages")],
+            dirs: vec![language("en", "Guide"), language("de", "Anleitung")],
+        }
+    }
+
+    #[test]
+    fn build_for_language() {
+        let config = config(None);
+        let root = languages_root();
+
+        for (language, guide) in &[("en", "Guide"), ("de", "Anleitung")] {
+            let navigation = Navigation::new(&config).with_language(language);
+            let links = navigation.build_for_language(&root).unwrap();
+
+            assert_eq!(
+                links,
+                vec![
+                    Link {
+                        path: String::from("/api"),
+                        source_path: Some(
+                            PathBuf::from("docs").join(language).join("api/README.md")
+                        ),
+                        title: String::from("API"),
+                        children: vec![Link {
+                            path: String::from("/api/auth"),
+                            source_path: Some(
+                                PathBuf::from("docs").join(language).join("api/auth.md")
+                            ),
+                            title: String::from("Auth"),
+                            children: vec![],
+                            ..Link::default()
+                        }],
+                        ..Link::default()
+                    },
+                    Link {
+                        path: String::from("/guide"),
+                        source_path: Some(PathBuf::from("docs").join(language).join("guide.md")),
+                        title: String::from(*guide),
+                        children: vec![],
+                        ..Link::default()
+                    },
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn build_for_language_rules() {
+        let config = config(None);
+        let root = languages_root();
+        let navigation = Navigation::new(&config).with_language("de");
+        let dir = &root.dirs[1];
+
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs/de/api/auth.md"),
+            LinkOptions::default(),
+        )];
+        let links = navigation.default_links(dir);
+
+        assert_eq!(
+            navigation.customize(&rules, &links).unwrap()[0].path,
+            "/api/auth"
+        );
+    }
+
+    #[test]
+    fn build_for_missing_language() {
+        let config = config(None);
+        let navigation = Navigation::new(&config).with_language("fr");
+
+        assert_eq!(
+            navigation.build_for_language(&languages_root()),
+            Err(NavigationError::UnknownLanguage {
+                language: String::from("fr")
+            })
+        );
+    }
+
+    #[test]
+    fn description_from_frontmatter() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page_with("one.md", "One", &[("description", "The first page")]),
+                page("two.md", "Two"),
+            ],
+            dirs: vec![],
+        };
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        assert_eq!(links[0].description, Some(String::from("The first page")));
+        assert_eq!(links[1].description, None);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&navigation.links_to_json(&links).unwrap()).unwrap();
+
+        assert_eq!(json["links"][0]["description"], "The first page");
+        assert_eq!(json["links"][1]["description"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn description_from_rule() {
+        let root = nested_root();
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs/one.md"),
+            LinkOptions {
+                description: Some(String::from("Start here")),
+                ..LinkOptions::default()
+            },
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(links[0].description, Some(String::from("Start here")));
+    }
+
+    #[test]
+    fn siblings() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        let paths = |links: Vec<&Link>| links.iter().map(|l| l.path.clone()).collect::<Vec<_>>();
+
+        assert_eq!(
+            paths(navigation.siblings(&links, "/child/three")),
+            vec!["/child/nested"]
+        );
+        assert_eq!(
+            paths(navigation.siblings(&links, "/one")),
+            vec!["/child", "/two"]
+        );
+        assert!(navigation.siblings(&links, "/missing").is_empty());
+    }
+
+    #[test]
+    fn badges_from_frontmatter() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page_with("one.md", "One", &[("badges", "New, Beta")]),
+                page("two.md", "Two"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![page_with(
+                    "child/README.md",
+                    "Nested Root",
+                    &[("badges", "Deprecated")],
+                )],
+                dirs: vec![],
+            }],
+        };
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        assert_eq!(links[0].badges, vec!["Deprecated"]);
+        assert_eq!(links[1].badges, vec!["New", "Beta"]);
+        assert_eq!(links[2].badges, Vec::<String>::new());
+    }
+
+    #[test]
+    fn badges_from_rule() {
+        let root = nested_root();
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs/one.md"),
+            LinkOptions {
+                badges: Some(vec![String::from("New")]),
+                ..LinkOptions::default()
+            },
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(links[0].badges, vec!["New"]);
+    }
+
+    #[test]
+    fn manual_menu_anchor() {
+        let root = nested_root();
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs/one.md"), LinkOptions::default()),
+            NavRule::File(
+                PathBuf::from("docs/one.md"),
+                LinkOptions {
+                    title: Some(String::from("Installation")),
+                    anchor: Some(String::from("Installation")),
+                    ..LinkOptions::default()
+                },
+            ),
+            NavRule::File(
+                PathBuf::from("docs/child/README.md"),
+                LinkOptions {
+                    anchor: Some(String::from("getting-help")),
+                    ..LinkOptions::default()
+                },
+            ),
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(
+            links[1],
+            Link {
+                path: String::from("/one#installation"),
+                source_path: Some(PathBuf::from("docs").join("one.md")),
+                title: String::from("Installation"),
+                children: vec![],
+                ..Link::default()
+            }
+        );
+        assert_eq!(links[2].path, "/child#getting-help");
+        assert!(links[2].children.is_empty());
+        assert_eq!(navigation.all_paths(&links), vec!["/one"]);
+    }
+
+    fn grouping_root() -> Directory {
+        Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: vec![
+                Directory {
+                    path: PathBuf::from("docs").join("guides"),
+                    docs: vec![page("guides/b.md", "Second"), page("guides/a.md", "First")],
+                    dirs: vec![],
+                },
+                Directory {
+                    path: PathBuf::from("docs").join("reference"),
+                    docs: vec![
+                        page("reference/README.md", "Reference"),
+                        page("reference/api.md", "API"),
+                    ],
+                    dirs: vec![],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn manual_menu_titled_directory_without_index() {
+        let rules = vec![NavRule::Dir(
+            PathBuf::from("docs/guides"),
+            Some(DirIncludeRule::WildCard),
+            DirOptions {
+                link: LinkOptions {
+                    title: Some(String::from("All Guides")),
+                    ..LinkOptions::default()
+                },
+                ..DirOptions::default()
+            },
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&grouping_root());
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(links[0].title, "All Guides");
+        assert_eq!(links[0].path, "");
+        assert_eq!(links[0].source_path, None);
+        assert!(links[0].is_group());
+        assert_eq!(links[0].children.len(), 2);
+    }
+
+    #[test]
+    fn manual_menu_titled_directory_without_index_link_to_first() {
+        let rules = vec![NavRule::Dir(
+            PathBuf::from("docs/guides"),
+            Some(DirIncludeRule::WildCard),
+            DirOptions {
+                link: LinkOptions {
+                    title: Some(String::from("All Guides")),
+                    ..LinkOptions::default()
+                },
+                link_to_first: true,
+                ..DirOptions::default()
+            },
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&grouping_root());
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(links[0].title, "All Guides");
+        assert_eq!(links[0].path, "/guides/a");
+        assert_eq!(
+            links[0].source_path,
+            Some(PathBuf::from("docs").join("guides/a.md"))
+        );
+    }
+
+    #[test]
+    fn manual_menu_titled_directory_with_index() {
+        let rules = vec![NavRule::Dir(
+            PathBuf::from("docs/reference"),
+            Some(DirIncludeRule::WildCard),
+            DirOptions {
+                link: LinkOptions {
+                    title: Some(String::from("API Reference")),
+                    ..LinkOptions::default()
+                },
+                ..DirOptions::default()
+            },
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&grouping_root());
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(links[0].title, "API Reference");
+        assert_eq!(links[0].path, "/reference");
+        assert_eq!(links[0].children.len(), 1);
+    }
+
+    #[test]
+    fn same_titles_are_sorted_by_path() {
+        let docs = |first: &str, second: &str| Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page(first, "Changelog"),
+                page(second, "Changelog"),
+            ],
+            dirs: vec![],
+        };
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+
+        for root in &[docs("b.md", "a.md"), docs("a.md", "b.md")] {
+            let links = navigation.build_for(root).unwrap();
+
+            assert_eq!(
+                links.iter().map(|l| l.path.as_str()).collect::<Vec<_>>(),
+                vec!["/a", "/b"]
+            );
+        }
+    }
+
+    #[test]
+    fn group_by_category() {
+        let config = config(Some("---\ntitle: My project\ngroup_by: category\n"));
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page_with("deploy.md", "Deploy", &[("category", "Operations")]),
+                page_with("api.md", "API", &[("category", "Reference")]),
+                page("faq.md", "FAQ"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page_with("child/README.md", "Child", &[("category", "Reference")]),
+                    page_with("child/backup.md", "Backup", &[("category", "Operations")]),
+                    page("child/misc.md", "Misc"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        let summary = links
+            .iter()
+            .map(|group| {
+                (
+                    group.title.as_str(),
+                    group.path.as_str(),
+                    group
+                        .children
+                        .iter()
+                        .map(|l| l.title.as_str())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("Operations", "", vec!["Backup", "Deploy"]),
+                ("Reference", "", vec!["API", "Child"]),
+                ("Uncategorized", "", vec!["FAQ", "Misc"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn manual_menu_dir_sort() {
+        let guides = |name: &str| Directory {
+            path: PathBuf::from("docs").join(name),
+            docs: vec![
+                page(&format!("{}/README.md", name), name),
+                page_with(&format!("{}/zulu.md", name), "Zulu", &[("order", "1")]),
+                page(&format!("{}/alpha.md", name), "Alpha"),
+                page(&format!("{}/mike.md", name), "Mike"),
+            ],
+            dirs: vec![],
+        };
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: vec![guides("ordered"), guides("alphabetical")],
+        };
+
+        let rules = vec![
+            NavRule::Dir(
+                PathBuf::from("docs/ordered"),
+                Some(DirIncludeRule::WildCard),
+                DirOptions::default(),
+            ),
+            NavRule::Dir(
+                PathBuf::from("docs/alphabetical"),
+                Some(DirIncludeRule::WildCard),
+                DirOptions {
+                    sort: SortMode::from_name("title"),
+                    ..DirOptions::default()
+                },
+            ),
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        let titles = |link: &Link| {
+            link.children
+                .iter()
+                .map(|l| l.title.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(titles(&links[0]), vec!["Zulu", "Alpha", "Mike"]);
+        assert_eq!(titles(&links[1]), vec!["Alpha", "Mike", "Zulu"]);
+    }
+
+    #[test]
+    fn leaf_and_section() {
+        let leaf = Link {
+            path: String::from("/one"),
+            title: String::from("One"),
+            ..Link::default()
+        };
+        let section = Link {
+            path: String::from("/child"),
+            title: String::from("Child"),
+            children: vec![leaf.clone()],
+            ..Link::default()
+        };
+        let group = Link {
+            title: String::from("Group"),
+            ..Link::default()
+        };
+        let divider = Link {
+            divider: true,
+            ..Link::default()
+        };
+
+        assert!(leaf.is_leaf());
+        assert!(!leaf.is_section());
+
+        assert!(section.is_section());
+        assert!(!section.is_leaf());
+
+        assert!(!group.is_leaf());
+        assert!(!group.is_section());
+
+        assert!(!divider.is_leaf());
+    }
+
+    #[test]
+    fn manual_menu_auto() {
+        let root = nested_root();
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs/two.md"), LinkOptions::default()),
+            NavRule::Auto,
+            NavRule::Dir(
+                PathBuf::from("docs/child"),
+                Some(DirIncludeRule::Explicit(vec![
+                    NavRule::File(PathBuf::from("docs/child/three.md"), LinkOptions::default()),
+                    NavRule::Auto,
+                ])),
+                DirOptions::default(),
+            ),
+        ];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(
+            links.iter().map(|l| l.path.as_str()).collect::<Vec<_>>(),
+            vec!["/two", "/one", "/child"]
+        );
+        assert_eq!(
+            links[2]
+                .children
+                .iter()
+                .map(|l| l.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/child/three", "/child/nested"]
+        );
+    }
+
+    #[test]
+    fn build_for_is_cached_until_the_directory_changes() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let mut root = nested_root();
+
+        let first = navigation.build_for(&root).unwrap();
+        assert_eq!(navigation.build_for(&root).unwrap(), first);
+
+        root.docs[1] = page("one.md", "Renamed");
+        let changed = navigation.build_for(&root).unwrap();
+
+        assert_ne!(changed, first);
+        assert!(changed.iter().any(|l| l.title == "Renamed"));
+    }
+
+    #[test]
+    fn ordered_directory_between_ordered_pages() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page_with("install.md", "Install", &[("order", "1")]),
+                page_with("deploy.md", "Deploy", &[("order", "3")]),
+                page("appendix.md", "Appendix"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("guides"),
+                docs: vec![
+                    page_with("guides/README.md", "Guides", &[("order", "2")]),
+                    page("guides/first.md", "First"),
+                ],
+                dirs: vec![],
+            }],
+        };
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        assert_eq!(
+            links.iter().map(|l| l.title.as_str()).collect::<Vec<_>>(),
+            vec!["Install", "Guides", "Deploy", "Appendix"]
+        );
+    }
+
+    #[test]
+    fn to_markdown() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let mut links = navigation.build_for(&nested_root()).unwrap();
+
+        links.push(Link {
+            divider: true,
+            ..Link::default()
+        });
+        links.push(Link {
+            title: String::from("Elsewhere"),
+            children: vec![Link {
+                path: String::from("https://github.com/Doctave/doctave"),
+                title: String::from("GitHub"),
+                ..Link::default()
+            }],
+            ..Link::default()
+        });
+
+        assert_eq!(
+            navigation.to_markdown(&links),
+            indoc! {"
+                - [Nested Root](/child)
+                  - [Nested Root](/child/nested)
+                    - [Four](/child/nested/four)
+                  - [Three](/child/three)
+                - [One](/one)
+                - [Two](/two)
+                - **Elsewhere**
+                  - [GitHub](https://github.com/Doctave/doctave)
+            "}
+        );
+    }
+
+    #[test]
+    fn manual_menu_custom_docs_dir() {
+        let config = config(Some("---\ntitle: My project\ndocs_dir: content\n"));
+        let root = Directory {
+            path: PathBuf::from("content"),
+            ..nested_root()
+        };
+
+        let rules = vec![
+            NavRule::File(PathBuf::from("content/two.md"), LinkOptions::default()),
+            NavRule::Dir(
+                PathBuf::from("content/child"),
+                Some(DirIncludeRule::Explicit(vec![NavRule::File(
+                    PathBuf::from("content/child/three.md"),
+                    LinkOptions::default(),
+                )])),
+                DirOptions::default(),
+            ),
+        ];
+
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(
+            navigation.all_paths(&links),
+            vec!["/two", "/child", "/child/three"]
+        );
+        assert!(navigation
+            .unreferenced(&root, &links)
+            .contains(&PathBuf::from("content").join("one.md")));
+    }
+
+    #[test]
+    fn not_found_suggestions() {
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs/child/nested/fuor.md"),
+            LinkOptions::default(),
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&nested_root());
+
+        let error = navigation.customize(&rules, &links).unwrap_err();
+        let message = format!("{}", error);
+
+        assert!(
+            message.contains("Did you mean /child/nested/four?"),
+            "Error message was: {}",
+            message
+        );
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("/child/three", "/child/three"), 0);
+        assert_eq!(edit_distance("/child/thre", "/child/three"), 1);
+        assert_eq!(edit_distance("/one", "/two"), 3);
+        assert_eq!(edit_distance("", "/two"), 4);
+    }
+
+    #[test]
+    fn subtree() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        let section = navigation.subtree(&links, "/child").unwrap();
+        assert_eq!(
+            section.iter().map(|l| l.path.as_str()).collect::<Vec<_>>(),
+            vec!["/child/nested", "/child/three"]
+        );
+
+        assert_eq!(navigation.subtree(&links, "/child/three"), Some(&[][..]));
+        assert_eq!(navigation.subtree(&links, "/missing"), None);
+    }
+
+    #[test]
+    fn path_to_uri_with_base_path() {
+        let config = config(Some("---\ntitle: My project\nbase_path: /docs/\n"));
+
+        assert_eq!(
+            Link::path_to_uri(Path::new("index.html"), &config),
+            "/docs/"
+        );
+        assert_eq!(
+            Link::path_to_uri(Path::new("one.html"), &config),
+            "/docs/one"
+        );
+        assert_eq!(
+            Link::path_to_uri(Path::new("child/index.html"), &config),
+            "/docs/child"
+        );
+    }
+
+    #[test]
+    fn path_to_uri_without_base_path() {
+        let config = config(None);
+
+        assert_eq!(config.base_path(), "");
+        assert_eq!(Link::path_to_uri(Path::new("index.html"), &config), "/");
+        assert_eq!(Link::path_to_uri(Path::new("one.html"), &config), "/one");
+    }
+
+    #[test]
+    fn manual_menu_with_base_path() {
+        let config = config(Some("---\ntitle: My project\nbase_path: docs\n"));
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&nested_root());
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs").join("two.md"), LinkOptions::default()),
+            NavRule::Dir(
+                PathBuf::from("docs").join("child"),
+                Some(DirIncludeRule::WildCard),
+                DirOptions::default(),
+            ),
+        ];
+
+        let customized = navigation.customize(&rules, &links).unwrap();
+        let paths = customized
+            .iter()
+            .map(|l| l.path.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(paths, vec!["/docs/two", "/docs/child"]);
+        assert_eq!(customized[1].children[0].path, "/docs/child/nested");
+        assert_eq!(customized[1].children[1].path, "/docs/child/three");
+    }
+
+    #[test]
+    fn walk_visits_links_in_reading_order() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        let mut titles = vec![];
+        navigation.walk(&links, |link, depth| {
+            titles.push(format!("{}{}", "-".repeat(depth), link.title))
+        });
+
+        assert_eq!(
+            titles,
+            vec![
+                "Nested Root",
+                "-Nested Root",
+                "--Four",
+                "-Three",
+                "One",
+                "Two"
+            ]
+        );
+    }
+
+    #[test]
+    fn walk_mut_changes_links_in_place() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let mut links = navigation.build_for(&nested_root()).unwrap();
+
+        navigation.walk_mut(&mut links, |link, _| link.title = link.title.to_uppercase());
+
+        assert_eq!(links[0].title, "NESTED ROOT");
+        assert_eq!(links[0].children[0].children[0].title, "FOUR");
+    }
+
+    #[test]
+    fn related() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        let related = navigation.related(
+            &links,
+            &[
+                PathBuf::from("docs").join("two.md"),
+                PathBuf::from("docs").join("child").join("three.md"),
+            ],
+        );
+
+        assert_eq!(
+            related,
+            vec![
+                Link {
+                    path: String::from("/two"),
+                    source_path: Some(PathBuf::from("docs").join("two.md")),
+                    title: String::from("Two"),
+                    ..Link::default()
                 },
-                Directory {
-                    path: PathBuf::from("docs").join("aa_child"),
-                    docs: vec![
-                        page("child2/README.md", "Index"),
-                        page("child2/001.md", "123"),
-                        page("child2/002.md", "aa"),
-                        page("child2/003.md", "cc"),
-                        page("child2/004.md", "bb"),
-                    ],
-                    dirs: vec![],
+                Link {
+                    path: String::from("/child/three"),
+                    source_path: Some(PathBuf::from("docs").join("child/three.md")),
+                    title: String::from("Three"),
+                    ..Link::default()
+                },
+            ]
+        );
+        assert!(navigation.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn related_skips_missing_pages() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        let related = navigation.related(&links, &[PathBuf::from("docs").join("missing.md")]);
+
+        assert!(related.is_empty());
+        assert_eq!(
+            navigation.take_warnings(),
+            vec![String::from(
+                "Could not find the related page docs/missing.md. It will be left out."
+            )]
+        );
+    }
+
+    fn basic_root() -> Directory {
+        Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("one.md", "One"),
+                page("two.md", "Two"),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page("child/README.md", "Nested Root"),
+                    page("child/three.md", "Three"),
+                ],
+                dirs: vec![],
+            }],
+        }
+    }
+
+    #[test]
+    fn group_order_files_first() {
+        let config = config(Some("---\ntitle: My project\ngroup_order: files_first\n"));
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&basic_root()).unwrap();
+
+        let titles = links.iter().map(|l| l.title.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(titles, vec!["One", "Two", "Nested Root"]);
+    }
+
+    #[test]
+    fn group_order_dirs_first() {
+        let config = config(Some("---\ntitle: My project\ngroup_order: dirs_first\n"));
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("alpha.md", "Alpha"),
+            ],
+            ..basic_root()
+        };
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        let titles = links.iter().map(|l| l.title.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(titles, vec!["Nested Root", "Alpha"]);
+    }
+
+    #[test]
+    fn group_order_mixed() {
+        let config = config(None);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("alpha.md", "Alpha"),
+            ],
+            ..basic_root()
+        };
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        let titles = links.iter().map(|l| l.title.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(titles, vec!["Alpha", "Nested Root"]);
+    }
+
+    #[test]
+    fn custom_index_filenames() {
+        let config = config(Some(
+            "---\ntitle: My project\nindex_filenames:\n  - _index.md\n",
+        ));
+        let mut index = page("child/_index.md", "Child");
+        index.set_index(config.is_index_file(&index.path));
+
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("one.md", "One")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![index, page("child/two.md", "Two")],
+                dirs: vec![],
+            }],
+        };
+        let navigation = Navigation::new(&config);
+
+        assert_eq!(
+            navigation.build_for(&root).unwrap(),
+            vec![
+                Link {
+                    path: String::from("/child"),
+                    source_path: Some(PathBuf::from("docs").join("child/_index.md")),
+                    title: String::from("Child"),
+                    children: vec![Link {
+                        path: String::from("/child/two"),
+                        source_path: Some(PathBuf::from("docs").join("child/two.md")),
+                        title: String::from("Two"),
+                        ..Link::default()
+                    }],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/one"),
+                    source_path: Some(PathBuf::from("docs").join("one.md")),
+                    title: String::from("One"),
+                    ..Link::default()
                 },
+            ]
+        );
+        assert_eq!(
+            Link::path_to_uri(Path::new("child/_index.md"), &config),
+            "/child"
+        );
+    }
+
+    #[test]
+    fn duplicate_uri() {
+        let config = config(None);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started"), page("one.md", "One")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("one"),
+                docs: vec![page("one/README.md", "Also One")],
+                dirs: vec![],
+            }],
+        };
+        let navigation = Navigation::new(&config);
+
+        let error = navigation.build_for(&root).unwrap_err();
+
+        assert_eq!(
+            error,
+            NavigationError::DuplicateUri {
+                uri: String::from("/one"),
+                first: PathBuf::from("docs").join("one.md"),
+                second: PathBuf::from("docs").join("one").join("README.md"),
+            }
+        );
+        assert_eq!(
+            format!("{}", error),
+            "Found two pages served from /one: docs/one.md and docs/one/README.md. \
+             Rename or move one of them."
+        );
+    }
+
+    #[test]
+    fn rules_limited_to_profiles() {
+        let config = config(None);
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+            NavRule::Only {
+                profiles: vec![String::from("internal")],
+                rule: Box::new(NavRule::File(
+                    PathBuf::from("docs").join("two.md"),
+                    LinkOptions::default(),
+                )),
+            },
+            NavRule::Only {
+                profiles: vec![String::from("public"), String::from("internal")],
+                rule: Box::new(NavRule::Link {
+                    title: String::from("Status"),
+                    url: String::from("https://status.example.com"),
+                    target: None,
+                    classes: vec![],
+                }),
+            },
+        ];
+
+        let paths_for = |profile: &str| {
+            let mut config = config.clone();
+            config.set_profile(profile);
+            let navigation = Navigation::new(&config);
+            let links = navigation.default_links(&nested_root());
+
+            navigation
+                .customize(&rules, &links)
+                .unwrap()
+                .into_iter()
+                .map(|l| l.path)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            paths_for("public"),
+            vec!["/one", "https://status.example.com"]
+        );
+        assert_eq!(
+            paths_for("internal"),
+            vec!["/one", "/two", "https://status.example.com"]
+        );
+
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&nested_root());
+        let paths = navigation
+            .customize(&rules, &links)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.path)
+            .collect::<Vec<_>>();
+
+        assert_eq!(paths, vec!["/one"]);
+    }
+
+    #[test]
+    fn to_json_envelope() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&navigation.links_to_json(&links).unwrap()).unwrap();
+
+        assert_eq!(json["schema_version"], NAV_SCHEMA_VERSION);
+        assert_eq!(json["links"], serde_json::to_value(&links).unwrap());
+    }
+
+    #[test]
+    fn external_link_target() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&nested_root());
+        let rules = vec![
+            NavRule::Link {
+                title: String::from("GitHub"),
+                url: String::from("https://github.com/Doctave/doctave"),
+                target: None,
+                classes: vec![],
+            },
+            NavRule::Link {
+                title: String::from("API"),
+                url: String::from("https://api.example.com"),
+                target: Some(String::from("_self")),
+                classes: vec![],
+            },
+            NavRule::Link {
+                title: String::from("Changelog"),
+                url: String::from("/changelog"),
+                target: None,
+                classes: vec![],
+            },
+        ];
+
+        let targets = navigation
+            .customize(&rules, &links)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.target)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            targets,
+            vec![
+                Some(String::from("_blank")),
+                Some(String::from("_self")),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn strip_markdown_code_spans() {
+        assert_eq!(
+            Link::strip_markdown("`serde` Integration"),
+            "serde Integration"
+        );
+        assert_eq!(Link::strip_markdown("The ``a`b`` type"), "The a`b type");
+        assert_eq!(Link::strip_markdown("Using `*_args`"), "Using *_args");
+    }
+
+    #[test]
+    fn strip_markdown_emphasis() {
+        assert_eq!(
+            Link::strip_markdown("**Breaking** changes"),
+            "Breaking changes"
+        );
+        assert_eq!(
+            Link::strip_markdown("An _important_ note"),
+            "An important note"
+        );
+        assert_eq!(
+            Link::strip_markdown("The snake_case style"),
+            "The snake_case style"
+        );
+        assert_eq!(
+            Link::strip_markdown("Escaped \\*stars\\*"),
+            "Escaped *stars*"
+        );
+    }
+
+    #[test]
+    fn strip_markdown_literal_asterisks() {
+        assert_eq!(Link::strip_markdown("2 * 3 = 6"), "2 * 3 = 6");
+        assert_eq!(Link::strip_markdown("Learning C*"), "Learning C*");
+        assert_eq!(Link::strip_markdown("Globs like *.md"), "Globs like *.md");
+        assert_eq!(Link::strip_markdown("2 * 3 is *six*"), "2 * 3 is six");
+        assert_eq!(
+            Link::strip_markdown("***Very*** important"),
+            "Very important"
+        );
+        assert_eq!(Link::strip_markdown("**Unclosed"), "**Unclosed");
+    }
+
+    #[test]
+    fn strip_markdown_links() {
+        assert_eq!(
+            Link::strip_markdown("Deploying with [Docker](https://docker.com)"),
+            "Deploying with Docker"
+        );
+        assert_eq!(
+            Link::strip_markdown("[**Bold** link](/x) [not a link]"),
+            "Bold link [not a link]"
+        );
+    }
+
+    #[test]
+    fn markdown_stripped_from_navigation_titles() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("serde.md", "`serde` Integration"),
+            ],
+            dirs: vec![],
+        };
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        assert_eq!(links[0].title, "serde Integration");
+        assert_eq!(root.docs[1].title(), "`serde` Integration");
+    }
+
+    #[test]
+    fn pinned_pages() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("beta.md", "Beta"),
+                page_with("faq.md", "FAQ", &[("pin", "bottom")]),
+                page("alpha.md", "Alpha"),
+                page_with("intro.md", "Introduction", &[("pin", "top")]),
+                page("gamma.md", "Gamma"),
+            ],
+            dirs: vec![],
+        };
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let titles = navigation
+            .build_for(&root)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.title)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            titles,
+            vec!["Introduction", "Alpha", "Beta", "Gamma", "FAQ"]
+        );
+    }
+
+    #[test]
+    fn pinned_pages_sort_among_themselves() {
+        let pinned = |title: &str, pin: Pin| Link {
+            title: title.to_owned(),
+            path: format!("/{}", title.to_lowercase()),
+            pin: Some(pin),
+            ..Link::default()
+        };
+        let mut links = vec![
+            pinned("Zulu", Pin::Top),
+            pinned("Omega", Pin::Bottom),
+            Link {
+                title: String::from("Middle"),
+                path: String::from("/middle"),
+                ..Link::default()
+            },
+            pinned("Alpha", Pin::Top),
+            pinned("Delta", Pin::Bottom),
+        ];
+
+        sort_links(&mut links, SortMode::Alphanumeric);
+
+        let titles = links.into_iter().map(|l| l.title).collect::<Vec<_>>();
+
+        assert_eq!(titles, vec!["Alpha", "Zulu", "Middle", "Delta", "Omega"]);
+    }
+
+    #[test]
+    fn diff() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let link = |path: &str, children: Vec<Link>| Link {
+            path: path.to_owned(),
+            title: path.to_owned(),
+            children,
+            ..Link::default()
+        };
+
+        let old = vec![
+            link("/guides", vec![link("/guides/setup", vec![])]),
+            link("/reference", vec![]),
+        ];
+        let new = vec![
+            link("/guides", vec![]),
+            link("/reference", vec![link("/guides/setup", vec![])]),
+            link("/faq", vec![]),
+        ];
+
+        let diff = navigation.diff(&old, &new);
+
+        assert_eq!(diff.added.keys().collect::<Vec<_>>(), vec!["/faq"]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(
+            diff.moved["/guides/setup"],
+            MovedLink {
+                link: link("/guides/setup", vec![]),
+                from: Some(String::from("/guides")),
+                to: Some(String::from("/reference")),
+            }
+        );
+        assert!(navigation.diff(&new, &new).is_empty());
+    }
+
+    fn ordered_root(name: &str, order: &str) -> Directory {
+        let path = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join(".order"), order).unwrap();
+
+        Directory {
+            path: path.clone(),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("alpha.md", "Alpha"),
+                page("beta.md", "Beta"),
+                page("gamma.md", "Gamma"),
             ],
+            dirs: vec![Directory {
+                path: path.join("child"),
+                docs: vec![page("child/README.md", "Child")],
+                dirs: vec![],
+            }],
+        }
+    }
+
+    fn titles(links: &[Link]) -> Vec<&str> {
+        links.iter().map(|l| l.title.as_str()).collect()
+    }
+
+    #[test]
+    fn order_file() {
+        let root = ordered_root(
+            "doctave_order_file_test",
+            "gamma.md\nchild\n\nalpha.md\nbeta.md\n",
+        );
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+        fs::remove_dir_all(&root.path).unwrap();
+
+        assert_eq!(titles(&links), vec!["Gamma", "Child", "Alpha", "Beta"]);
+        assert!(navigation.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn partial_order_file() {
+        let root = ordered_root("doctave_partial_order_file_test", "beta.md\nmissing.md\n");
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+        fs::remove_dir_all(&root.path).unwrap();
+
+        assert_eq!(titles(&links), vec!["Beta", "Alpha", "Child", "Gamma"]);
+
+        let warnings = navigation.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("lists missing.md, which is not in the directory"));
+    }
+
+    #[test]
+    fn strip_index() {
+        let config = config(None);
+
+        assert_eq!(
+            Link::strip_index(Path::new("child/index.html"), &config),
+            Some(PathBuf::from("child"))
+        );
+        assert_eq!(
+            Link::strip_index(Path::new("child/README.md"), &config),
+            Some(PathBuf::from("child"))
+        );
+        assert_eq!(
+            Link::strip_index(Path::new("index.html"), &config),
+            Some(PathBuf::new())
+        );
+        assert_eq!(
+            Link::strip_index(Path::new("child/three.html"), &config),
+            None
+        );
+    }
+
+    #[test]
+    fn strip_index_windows_paths() {
+        let config = config(None);
+
+        assert_eq!(
+            Link::strip_index(Path::new("child\\nested\\index.html"), &config),
+            Some(PathBuf::from("child/nested"))
+        );
+        assert_eq!(
+            Link::strip_index(Path::new("child\\three.html"), &config),
+            None
+        );
+    }
+
+    #[test]
+    fn to_forward_slashes() {
+        assert_eq!(
+            Link::to_forward_slashes(Path::new("child/three")),
+            "child/three"
+        );
+        assert_eq!(Link::to_forward_slashes(Path::new("")), "");
+        assert_eq!(
+            Link::to_forward_slashes(&PathBuf::from("child").join("nested").join("four")),
+            "child/nested/four"
+        );
+    }
+
+    #[test]
+    fn to_forward_slashes_windows_paths() {
+        assert_eq!(
+            Link::to_forward_slashes(Path::new("child\\nested\\four")),
+            "child/nested/four"
+        );
+        assert_eq!(
+            Link::path_to_uri(Path::new("child\\three.html"), &config(None)),
+            "/child/three"
+        );
+    }
+
+    #[test]
+    fn generated_section() {
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started"), page("one.md", "One")],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("plugins"),
+                docs: vec![
+                    page("plugins/search.md", "Search"),
+                    page("plugins/analytics.md", "Analytics"),
+                    page("plugins/mermaid.md", "Mermaid"),
+                ],
+                dirs: vec![],
+            }],
         };
+        let rules = vec![
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
+            NavRule::Generated {
+                title: String::from("Plugins"),
+                from: PathBuf::from("docs").join("plugins"),
+            },
+        ];
 
+        let config = config(None);
         let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&root);
+
+        let page = |path: &str, title: &str, source: &str| Link {
+            path: path.to_owned(),
+            title: title.to_owned(),
+            source_path: Some(PathBuf::from("docs").join(source)),
+            ..Link::default()
+        };
 
         assert_eq!(
-            navigation.build_for(&root),
+            navigation.customize(&rules, &links).unwrap(),
             vec![
+                page("/one", "One", "one.md"),
                 Link {
-                    path: String::from("/002"),
-                    title: String::from("11"),
-                    children: vec![],
-                },
-                Link {
-                    path: String::from("/child"),
-                    title: String::from("Index"),
+                    path: String::new(),
+                    title: String::from("Plugins"),
                     children: vec![
-                        Link {
-                            path: String::from("/child/004"),
-                            title: String::from("11"),
-                            children: vec![],
-                        },
-                        Link {
-                            path: String::from("/child/002"),
-                            title: String::from("22"),
-                            children: vec![],
-                        },
-                        Link {
-                            path: String::from("/child/003"),
-                            title: String::from("AA"),
-                            children: vec![],
-                        },
-                        Link {
-                            path: String::from("/child/001"),
-                            title: String::from("BB"),
-                            children: vec![],
-                        },
-                    ]
+                        page("/plugins/analytics", "Analytics", "plugins/analytics.md"),
+                        page("/plugins/mermaid", "Mermaid", "plugins/mermaid.md"),
+                        page("/plugins/search", "Search", "plugins/search.md"),
+                    ],
+                    ..Link::default()
                 },
+            ]
+        );
+    }
+
+    fn drafts_root() -> Directory {
+        Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("one.md", "One"),
+                page_with("two.md", "Two", &[("status", "draft")]),
+            ],
+            dirs: vec![Directory {
+                path: PathBuf::from("docs").join("child"),
+                docs: vec![
+                    page_with("child/README.md", "Child", &[("status", "draft")]),
+                    page("child/three.md", "Three"),
+                ],
+                dirs: vec![],
+            }],
+        }
+    }
+
+    #[test]
+    fn drafts_excluded() {
+        let mut config = config(None);
+        config.set_build_mode(BuildMode::Release);
+        let navigation = Navigation::new(&config);
+
+        assert_eq!(
+            navigation.build_for(&drafts_root()).unwrap(),
+            vec![Link {
+                path: String::from("/one"),
+                source_path: Some(PathBuf::from("docs").join("one.md")),
+                title: String::from("One"),
+                ..Link::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn drafts_included() {
+        let mut config = config(None);
+        config.set_build_mode(BuildMode::Dev);
+        let navigation = Navigation::new(&config);
+
+        assert_eq!(
+            navigation
+                .build_for(&drafts_root())
+                .unwrap()
+                .iter()
+                .map(|l| l.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/child", "/one", "/two"]
+        );
+    }
+
+    #[test]
+    fn order_independent_of_listing() {
+        let dir = |name: &str, docs: Vec<Document>| Directory {
+            path: PathBuf::from("docs").join(name),
+            docs,
+            dirs: vec![],
+        };
+        let listed = |mut docs: Vec<Document>, mut dirs: Vec<Directory>, reverse: bool| {
+            if reverse {
+                docs.reverse();
+                dirs.reverse();
+            }
+            Directory {
+                path: PathBuf::from("docs"),
+                docs,
+                dirs,
+            }
+        };
+        let docs = || {
+            vec![
+                page("README.md", "Getting Started"),
+                page("a.md", "Same"),
+                page("b.md", "Same"),
+                page("c.md", "Other"),
+            ]
+        };
+        let dirs = || {
+            vec![
+                dir("one", vec![page("one/page.md", "Page")]),
+                dir("two", vec![page("two/page.md", "Page")]),
+            ]
+        };
+
+        let config = config(None);
+        let first = Navigation::new(&config);
+        let second = Navigation::new(&config);
+
+        assert_eq!(
+            first.build_for(&listed(docs(), dirs(), false)).unwrap(),
+            second.build_for(&listed(docs(), dirs(), true)).unwrap()
+        );
+        assert_eq!(first.take_warnings(), second.take_warnings());
+    }
+
+    #[test]
+    fn ancestors() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        assert_eq!(
+            navigation
+                .ancestors(&links, "/child/nested/four")
+                .iter()
+                .map(|l| l.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/child", "/child/nested"]
+        );
+    }
+
+    #[test]
+    fn ancestors_of_top_level_page() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&nested_root()).unwrap();
+
+        assert!(navigation.ancestors(&links, "/one").is_empty());
+        assert!(navigation.ancestors(&links, "/missing").is_empty());
+    }
+
+    #[test]
+    fn sort_links_semver() {
+        let link = |title: &str| Link {
+            path: format!("/{}", Link::slugify(title)),
+            title: title.to_owned(),
+            ..Link::default()
+        };
+        let mut links = vec![
+            link("v1.10"),
+            link("Upgrading"),
+            link("v1.9"),
+            link("v2.0"),
+            link("v1.9.1"),
+        ];
+
+        sort_links(&mut links, SortMode::Semver);
+
+        assert_eq!(
+            links.iter().map(|l| l.title.as_str()).collect::<Vec<_>>(),
+            vec!["v1.9", "v1.9.1", "v1.10", "v2.0", "Upgrading"]
+        );
+    }
+
+    #[test]
+    fn manual_menu_unlinked_directory() {
+        let rules = vec![NavRule::Dir(
+            PathBuf::from("docs/reference"),
+            Some(DirIncludeRule::WildCard),
+            DirOptions {
+                unlinked: true,
+                ..DirOptions::default()
+            },
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&grouping_root());
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(links[0].path, "");
+        assert!(links[0].is_group());
+        assert_eq!(links[0].children.len(), 1);
+
+        let child = links[0].children[0].path.clone();
+        assert_eq!(navigation.breadcrumbs(&links, &child).len(), 1);
+        assert_eq!(navigation.all_paths(&links), vec![child]);
+    }
+
+    #[test]
+    fn source_paths() {
+        let root = nested_root();
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.build_for(&root).unwrap();
+
+        let mut sources = vec![];
+        navigation.walk(&links, |link, _| {
+            sources.push((link.path.clone(), link.source_path.clone().unwrap()));
+        });
+
+        assert_eq!(
+            sources,
+            vec![
+                (
+                    String::from("/child"),
+                    PathBuf::from("docs").join("child/README.md")
+                ),
+                (
+                    String::from("/child/nested"),
+                    PathBuf::from("docs").join("child/nested/README.md")
+                ),
+                (
+                    String::from("/child/nested/four"),
+                    PathBuf::from("docs").join("child/nested/four.md")
+                ),
+                (
+                    String::from("/child/three"),
+                    PathBuf::from("docs").join("child/three.md")
+                ),
+                (String::from("/one"), PathBuf::from("docs").join("one.md")),
+                (String::from("/two"), PathBuf::from("docs").join("two.md")),
+            ]
+        );
+    }
+
+    #[test]
+    fn first_leaf() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let section = Link {
+            path: String::from("/guides"),
+            title: String::from("Guides"),
+            children: vec![
                 Link {
-                    path: String::from("/child2"),
-                    title: String::from("Index"),
-                    children: vec![
-                        Link {
-                            path: String::from("/child2/001"),
-                            title: String::from("123"),
-                            children: vec![]
-                        },
-                        Link {
-                            path: String::from("/child2/002"),
-                            title: String::from("aa"),
-                            children: vec![]
-                        },
-                        Link {
-                            path: String::from("/child2/004"),
-                            title: String::from("bb"),
-                            children: vec![]
-                        },
-                        Link {
-                            path: String::from("/child2/003"),
-                            title: String::from("cc"),
-                            children: vec![]
-                        },
-                    ]
+                    divider: true,
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("https://example.com"),
+                    title: String::from("Example"),
+                    ..Link::default()
                 },
                 Link {
-                    path: String::from("/001"),
-                    title: String::from("bb"),
-                    children: vec![],
+                    path: String::from("/guides/basics"),
+                    title: String::from("Basics"),
+                    children: vec![Link {
+                        path: String::from("/guides/basics/install"),
+                        title: String::from("Install"),
+                        ..Link::default()
+                    }],
+                    ..Link::default()
+                },
+                Link {
+                    path: String::from("/guides/advanced"),
+                    title: String::from("Advanced"),
+                    ..Link::default()
                 },
             ],
-        )
+            ..Link::default()
+        };
+
+        assert_eq!(
+            navigation.first_leaf(&section).map(|l| l.path.as_str()),
+            Some("/guides/basics/install")
+        );
     }
 
     #[test]
-    fn manual_menu_simple() {
-        let root = Directory {
-            path: PathBuf::from("docs"),
-            docs: vec![
-                page("README.md", "Getting Started"),
-                page("one.md", "One"),
-                page("two.md", "Two"),
-            ],
-            dirs: vec![Directory {
-                path: PathBuf::from("docs").join("child"),
-                docs: vec![
-                    page("child/README.md", "Nested Root"),
-                    page("child/three.md", "Three"),
-                ],
-                dirs: vec![],
-            }],
+    fn first_leaf_of_empty_section() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let section = Link {
+            path: String::from("/guides"),
+            title: String::from("Guides"),
+            ..Link::default()
+        };
+
+        assert_eq!(navigation.first_leaf(&section), None);
+    }
+
+    #[test]
+    fn mark_active_max_expand_depth() {
+        let section = |path: &str, children: Vec<Link>| Link {
+            path: path.to_owned(),
+            title: path.to_owned(),
+            expanded: false,
+            children,
+            ..Link::default()
         };
+        let mut links = vec![section(
+            "/a",
+            vec![section(
+                "/a/b",
+                vec![section("/a/b/c", vec![section("/a/b/c/page", vec![])])],
+            )],
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        navigation.mark_active(&mut links, "/a/b/c/page", Some(2));
+
+        let a = &links[0];
+        let b = &a.children[0];
+        let c = &b.children[0];
+
+        assert_eq!((a.active_trail, a.expanded), (true, false));
+        assert_eq!((b.active_trail, b.expanded), (true, true));
+        assert_eq!((c.active_trail, c.expanded), (true, true));
+        assert!(c.children[0].active);
+    }
+
+    #[test]
+    fn reading_order() {
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+
+        assert_eq!(
+            navigation.reading_order(&nested_root()).unwrap(),
+            vec![
+                PathBuf::from("docs").join("child/README.md"),
+                PathBuf::from("docs").join("child/nested/README.md"),
+                PathBuf::from("docs").join("child/nested/four.md"),
+                PathBuf::from("docs").join("child/three.md"),
+                PathBuf::from("docs").join("one.md"),
+                PathBuf::from("docs").join("two.md"),
+            ]
+        );
+    }
 
+    #[test]
+    fn reading_order_of_customized_navigation() {
+        let root = nested_root();
         let rules = vec![
-            NavRule::File(PathBuf::from("docs/one.md")),
-            NavRule::Dir(PathBuf::from("docs/child"), Some(DirIncludeRule::WildCard)),
+            NavRule::File(PathBuf::from("docs").join("two.md"), LinkOptions::default()),
+            NavRule::Link {
+                title: String::from("Example"),
+                url: String::from("https://example.com"),
+                target: None,
+                classes: vec![],
+            },
+            NavRule::Group {
+                title: String::from("Heading"),
+                children: vec![NavRule::Dir(
+                    PathBuf::from("docs").join("child").join("nested"),
+                    Some(DirIncludeRule::WildCard),
+                    DirOptions::default(),
+                )],
+            },
+            NavRule::File(PathBuf::from("docs").join("one.md"), LinkOptions::default()),
         ];
 
         let config = config(None);
         let navigation = Navigation::new(&config);
-        let links: Vec<Link> = (&root).into();
+        let links = navigation.default_links(&root);
+        let links = navigation.customize(&rules, &links).unwrap();
 
         assert_eq!(
-            navigation.customize(&rules, &links),
+            navigation.sources_in_order(&links),
             vec![
-                Link {
-                    path: String::from("/one"),
-                    title: String::from("One"),
-                    children: vec![],
-                },
-                Link {
-                    path: String::from("/child"),
-                    title: String::from("Nested Root"),
-                    children: vec![Link {
-                        path: String::from("/child/three"),
-                        title: String::from("Three"),
-                        children: vec![],
-                    },],
-                },
+                PathBuf::from("docs").join("two.md"),
+                PathBuf::from("docs").join("child/nested/README.md"),
+                PathBuf::from("docs").join("child/nested/four.md"),
+                PathBuf::from("docs").join("one.md"),
             ]
-        )
+        );
     }
 
     #[test]
-    fn manual_menu_nested() {
+    fn manual_menu_classes() {
+        let rules = vec![NavRule::File(
+            PathBuf::from("docs").join("one.md"),
+            LinkOptions {
+                classes: vec![String::from("highlight")],
+                ..LinkOptions::default()
+            },
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&nested_root());
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(links[0].path, "/one");
+        assert_eq!(links[0].classes, vec![String::from("highlight")]);
+    }
+
+    #[test]
+    fn build_from_docs() {
+        let docs = vec![
+            page("README.md", "Getting Started"),
+            page("one.md", "One"),
+            page("two.md", "Two"),
+            page("child/README.md", "Nested Root"),
+            page("child/three.md", "Three"),
+            page("child/nested/README.md", "Nested Root"),
+            page("child/nested/four.md", "Four"),
+        ];
+
+        let config = config(None);
+        let from_docs = Navigation::new(&config).build_from_docs(&docs).unwrap();
+        let from_dir = Navigation::new(&config).build_for(&nested_root()).unwrap();
+
+        assert_eq!(from_docs, from_dir);
+    }
+
+    #[test]
+    fn warnings_from_nested_directories_are_in_path_order() {
         let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: (0..16)
+                .rev()
+                .map(|i| Directory {
+                    path: PathBuf::from("docs").join(format!("dir-{:02}", i)),
+                    docs: vec![page(&format!("dir-{:02}/page.md", i), "Page")],
+                    dirs: vec![],
+                })
+                .collect(),
+        };
+
+        let expected = (0..16)
+            .map(|i| {
+                format!(
+                    "The directory docs/dir-{:02} does not have a README.md. \
+                     Using \"Dir {:02}\" as its title in the navigation.",
+                    i, i
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let config = config(None);
+        for _ in 0..5 {
+            let navigation = Navigation::new(&config);
+            navigation.build_for(&root).unwrap();
+
+            assert_eq!(navigation.take_warnings(), expected);
+        }
+    }
+
+    #[test]
+    fn index_sort_from_frontmatter() {
+        let root = |sort: &str| Directory {
             path: PathBuf::from("docs"),
             docs: vec![
-                page("README.md", "Getting Started"),
-                page("one.md", "One"),
-                page("two.md", "Two"),
+                page_with("README.md", "Getting Started", &[("sort", sort)]),
+                page("b.md", "Beta"),
+                page_with("a.md", "Zulu", &[("order", "1")]),
+                page("c.md", "Alpha"),
             ],
-            dirs: vec![Directory {
-                path: PathBuf::from("docs").join("child"),
-                docs: vec![
-                    page("child/README.md", "Nested Root"),
-                    page("child/three.md", "Three"),
-                ],
-                dirs: vec![Directory {
-                    path: PathBuf::from("docs").join("child").join("nested"),
-                    docs: vec![
-                        page("child/nested/README.md", "Nested Root"),
-                        page("child/nested/four.md", "Four"),
-                    ],
-                    dirs: vec![],
-                }],
-            }],
+            dirs: vec![],
         };
+        let config = config(None);
+        let titles = |links: Vec<Link>| links.into_iter().map(|l| l.title).collect::<Vec<_>>();
 
-        let rules = vec![
-            NavRule::File(PathBuf::from("docs").join("one.md")),
-            NavRule::Dir(
-                PathBuf::from("docs").join("child"),
-                Some(DirIncludeRule::Explicit(vec![NavRule::Dir(
-                    PathBuf::from("docs").join("child").join("nested"),
-                    Some(DirIncludeRule::Explicit(vec![NavRule::File(
-                        PathBuf::from("docs")
-                            .join("child")
-                            .join("nested")
-                            .join("four.md"),
-                    )])),
-                )])),
-            ),
-        ];
+        let navigation = Navigation::new(&config);
+        assert_eq!(
+            titles(navigation.build_for(&root("title")).unwrap()),
+            vec!["Alpha", "Beta", "Zulu"]
+        );
+        assert_eq!(
+            titles(navigation.build_for(&root("none")).unwrap()),
+            vec!["Zulu", "Beta", "Alpha"]
+        );
+        assert!(navigation.take_warnings().is_empty());
+
+        assert_eq!(
+            titles(navigation.build_for(&root("backwards")).unwrap()),
+            vec!["Zulu", "Alpha", "Beta"]
+        );
+        assert_eq!(
+            navigation.take_warnings(),
+            vec![String::from(
+                "docs/README.md sets an unknown sort \"backwards\". Expected one of \
+                 title, order, reverse, date, semver, none. \
+                 Its directory will be sorted as usual."
+            )]
+        );
+    }
 
+    #[test]
+    fn annotate_ids() {
         let config = config(None);
         let navigation = Navigation::new(&config);
-        let links: Vec<Link> = (&root).into();
+        let mut links = navigation.build_for(&nested_root()).unwrap();
+
+        navigation.annotate_ids(&mut links);
+
+        let id = |path: &str| navigation.find(&links, path).unwrap().id.clone();
+
+        assert_eq!(id("/one"), "nav--one");
+        assert_eq!(id("/child"), "nav--child");
+        assert_eq!(id("/child/nested/four"), "nav--child--nested--four");
+
+        // A directory with its overview, a page listed twice and two groups
+        // with the same title each get an id of their own
+        let group = |title: &str| Link {
+            title: String::from(title),
+            path: String::new(),
+            ..Link::default()
+        };
+        let mut links = vec![
+            Link {
+                path: String::from("/child"),
+                children: vec![Link {
+                    path: String::from("/child"),
+                    ..Link::default()
+                }],
+                ..Link::default()
+            },
+            group("Guides"),
+            Link {
+                path: String::from("/one"),
+                ..Link::default()
+            },
+            group("Guides"),
+            Link {
+                path: String::from("/one"),
+                ..Link::default()
+            },
+        ];
 
+        navigation.annotate_ids(&mut links);
+
+        let mut ids = vec![];
+        navigation.walk(&links, |link, _| ids.push(link.id.clone()));
         assert_eq!(
-            navigation.customize(&rules, &links),
+            ids,
             vec![
-                Link {
-                    path: String::from("/one"),
-                    title: String::from("One"),
-                    children: vec![]
-                },
-                Link {
-                    path: String::from("/child"),
-                    title: String::from("Nested Root"),
-                    children: vec![Link {
-                        path: String::from("/child/nested"),
-                        title: String::from("Nested Root"),
-                        children: vec![Link {
-                            path: String::from("/child/nested/four"),
-                            title: String::from("Four"),
-                            children: vec![]
-                        },]
-                    }]
-                }
+                "nav--child",
+                "nav--child---2",
+                "nav-group--Guides",
+                "nav--one",
+                "nav-group--Guides---2",
+                "nav--one---2",
             ]
         );
     }
 
     #[test]
-    fn manual_menu_file_from_nested_directory() {
-        let root = Directory {
+    fn manual_menu_directory_with_index_link_to_first() {
+        let rules = vec![NavRule::Dir(
+            PathBuf::from("docs/reference"),
+            Some(DirIncludeRule::WildCard),
+            DirOptions {
+                link_to_first: true,
+                overview: Some(String::from("Overview")),
+                ..DirOptions::default()
+            },
+        )];
+
+        let config = config(None);
+        let navigation = Navigation::new(&config);
+        let links = navigation.default_links(&grouping_root());
+        let links = navigation.customize(&rules, &links).unwrap();
+
+        assert_eq!(links[0].path, "/reference/api");
+        assert_eq!(links[0].children[0].title, "Overview");
+        assert_eq!(links[0].children[0].path, "/reference");
+    }
+
+    #[test]
+    fn merge_dirs_like_build_for() {
+        let guides = Directory {
             path: PathBuf::from("docs"),
-            docs: vec![page("README.md", "Getting Started")],
+            docs: vec![
+                page_with("README.md", "Getting Started", &[("sort", "reverse")]),
+                page("tutorial.md", "Tutorial"),
+            ],
+            dirs: vec![],
+        };
+        let api = Directory {
+            path: PathBuf::from("api"),
+            docs: vec![],
             dirs: vec![Directory {
-                path: PathBuf::from("docs").join("child"),
+                path: PathBuf::from("api").join("reference"),
                 docs: vec![
-                    page("child/README.md", "Nested Root"),
-                    page("child/three.md", "Three"),
+                    page("reference/README.md", "Reference"),
+                    page("reference/auth.md", "Authentication"),
                 ],
                 dirs: vec![],
             }],
         };
 
-        let rules = vec![NavRule::File(
-            PathBuf::from("docs").join("child").join("three.md"),
-        )];
-
         let config = config(None);
         let navigation = Navigation::new(&config);
-        let links: Vec<Link> = (&root).into();
+        let links = navigation
+            .build_for(&navigation.merge_dirs(&[&guides, &api]).unwrap())
+            .unwrap();
 
+        // The index of the docs directory sorts the merged top level
         assert_eq!(
-            navigation.customize(&rules, &links),
-            vec![Link {
-                path: String::from("/child/three"),
-                title: String::from("Three"),
-                children: vec![]
-            },]
+            links.iter().map(|l| l.path.clone()).collect::<Vec<_>>(),
+            vec!["/tutorial", "/reference"]
         );
+        assert_eq!(links[1].children[0].path, "/reference/auth");
+
+        let merged = navigation.merge_dirs(&[&guides, &api]).unwrap();
+        navigation.build_for(&merged).unwrap();
+        assert_eq!(navigation.cache.hits(), 1);
+
+        let mut changed = api.clone();
+        changed.dirs[0]
+            .docs
+            .push(page("reference/users.md", "Users"));
+        let links = navigation
+            .build_for(&navigation.merge_dirs(&[&guides, &changed]).unwrap())
+            .unwrap();
+        assert_eq!(navigation.cache.hits(), 1);
+        assert_eq!(links[1].children.len(), 2);
     }
 
     #[test]
-    fn manual_menu_file_from_parent_directory() {
-        let root = Directory {
-            path: PathBuf::from("docs"),
-            docs: vec![page("README.md", "Getting Started"), page("one.md", "One")],
+    fn validate_many() {
+        let config = config(None);
+        let docs = Directory {
+            path: Path::new("project").join("docs"),
+            docs: vec![
+                page("README.md", "Getting Started"),
+                page("guide.md", "Guide"),
+            ],
+            dirs: vec![],
+        };
+        let api = Directory {
+            path: Path::new("project").join("api"),
+            docs: vec![],
             dirs: vec![Directory {
-                path: PathBuf::from("docs").join("child"),
-                docs: vec![page("child/README.md", "Nested Root")],
+                path: Path::new("project").join("api").join("reference"),
+                docs: vec![page("reference/client.md", "Client")],
                 dirs: vec![],
             }],
         };
+        let slugged = Directory {
+            path: Path::new("project").join("api"),
+            docs: vec![page_with("client.md", "Client", &[("slug", "guide")])],
+            dirs: vec![],
+        };
+        let navigation = Navigation::new(&config);
 
-        let rules = vec![NavRule::Dir(
-            PathBuf::from("docs").join("child"),
-            Some(DirIncludeRule::Explicit(vec![NavRule::File(
-                PathBuf::from("docs").join("one.md"),
-            )])),
-        )];
+        assert_eq!(navigation.validate_many(&[&docs, &api]), Ok(()));
+        assert_eq!(
+            navigation.validate_many(&[&docs, &slugged]),
+            Err(NavigationError::Collision {
+                path: String::from("/guide")
+            })
+        );
+        assert_eq!(
+            navigation.unreferenced(&api, &[]),
+            vec![PathBuf::from("api").join("reference").join("client.md")]
+        );
+    }
+
+    #[test]
+    fn directory_with_generated_index() {
+        let generated = |dir: &str| {
+            let mut frontmatter = BTreeMap::new();
+            frontmatter.insert(String::from("title"), dir.to_owned());
+
+            Document::generated_index(
+                &Path::new(dir).join("README.md"),
+                String::from("Made up"),
+                frontmatter,
+            )
+        };
 
         let config = config(None);
+        let root = Directory {
+            path: PathBuf::from("docs"),
+            docs: vec![page("README.md", "Getting Started")],
+            dirs: vec![
+                Directory {
+                    path: PathBuf::from("docs").join("empty"),
+                    docs: vec![generated("empty")],
+                    dirs: vec![],
+                },
+                Directory {
+                    path: PathBuf::from("docs").join("getting-started-guides"),
+                    docs: vec![
+                        generated("getting-started-guides"),
+                        page("getting-started-guides/a.md", "First"),
+                    ],
+                    dirs: vec![],
+                },
+            ],
+        };
+
         let navigation = Navigation::new(&config);
-        let links: Vec<Link> = (&root).into();
+        let links = navigation.build_for(&root).unwrap();
 
+        // Directories with nothing to link to are left out without a warning
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].title, "Getting Started Guides");
+        assert_eq!(links[0].path, "/getting-started-guides/a");
         assert_eq!(
-            navigation.customize(&rules, &links),
-            vec![Link {
-                path: String::from("/child"),
-                title: String::from("Nested Root"),
-                children: vec![Link {
-                    path: String::from("/one"),
-                    title: String::from("One"),
-                    children: vec![],
-                }]
-            },]
+            navigation.take_warnings(),
+            vec![
+                "The directory docs/getting-started-guides does not have a README.md. \
+                 Using \"Getting Started Guides\" as its title in the navigation."
+            ]
         );
+        assert!(navigation.unreferenced(&root, &links).is_empty());
     }
 }
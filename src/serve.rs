@@ -6,6 +6,7 @@ use crossbeam_channel::bounded;
 
 use crate::config::Config;
 use crate::livereload_server::LivereloadServer;
+use crate::navigation::Navigation;
 use crate::preview_server::PreviewServer;
 use crate::site::Site;
 use crate::watcher::Watcher;
@@ -38,13 +39,19 @@ impl ServeCommand {
         // Do initial build ---------------------------
 
         let start = Instant::now();
-        cmd.site.build().unwrap();
+        let warnings = cmd.site.build().unwrap();
         let duration = start.elapsed();
 
+        for warning in warnings {
+            bunt::writeln!(stdout, "{$bold}{$yellow}Warning:{/$}{/$} {}\n", warning)?;
+        }
+
         // Watcher ------------------------------------
 
         let (watch_snd, watch_rcv) = bounded(128);
-        let watcher = Watcher::new(vec![cmd.config.project_root().join("docs")], watch_snd);
+        let mut watched = vec![cmd.config.docs_dir().to_path_buf()];
+        watched.extend(cmd.config.extra_docs_dirs().iter().cloned());
+        let watcher = Watcher::new(watched, watch_snd);
         thread::Builder::new()
             .name("watcher".into())
             .spawn(move || watcher.run())
@@ -79,10 +86,41 @@ impl ServeCommand {
         for (path, msg) in watch_rcv {
             bunt::writeln!(stdout, "    File {$bold}{}{/$} {}.", path.display(), msg)?;
 
+            let before = cmd.site.navigation_cache().links();
+
             let start = Instant::now();
-            cmd.site.build().unwrap();
+            let warnings = cmd.site.build().unwrap();
             let duration = start.elapsed();
 
+            for warning in warnings {
+                bunt::writeln!(stdout, "    {$bold}{$yellow}Warning:{/$}{/$} {}", warning)?;
+            }
+
+            // Show how the sidebar changed, so that surprising moves are
+            // noticed before the docs are published
+            if let (Some(before), Some(after)) = (before, cmd.site.navigation_cache().links()) {
+                let diff = Navigation::new(&cmd.config).diff(&before, &after);
+
+                if !diff.is_empty() {
+                    bunt::writeln!(stdout, "    {$bold}Navigation changed:{/$}")?;
+                }
+                for path in diff.added.keys() {
+                    bunt::writeln!(stdout, "      Added {}", path)?;
+                }
+                for path in diff.removed.keys() {
+                    bunt::writeln!(stdout, "      Removed {}", path)?;
+                }
+                for (path, moved) in &diff.moved {
+                    bunt::writeln!(
+                        stdout,
+                        "      Moved {} from {} to {}",
+                        path,
+                        moved.from.as_deref().unwrap_or("the top level"),
+                        moved.to.as_deref().unwrap_or("the top level")
+                    )?;
+                }
+            }
+
             bunt::writeln!(stdout, "    Site rebuilt in {$bold}{:?}{/$}\n", duration)?;
 
             reload_send.send(()).unwrap();
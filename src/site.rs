@@ -1,6 +1,8 @@
 use std::fs;
+use std::sync::Arc;
 
 use crate::config::Config;
+use crate::navigation::NavigationCache;
 use crate::site_generator::SiteGenerator;
 use crate::{Error, Result};
 
@@ -27,12 +29,22 @@ impl std::fmt::Display for BuildMode {
 /// located. Only cares about the destination directory.
 pub struct Site {
     config: Config,
+    /// The navigation of the last build, reused when the site is rebuilt
+    /// without changes to the navigation
+    navigation_cache: Arc<NavigationCache>,
 }
 
 impl Site {
     /// Create a new handle to a site output directory.
     pub fn new(config: Config) -> Site {
-        Site { config }
+        Site {
+            config,
+            navigation_cache: Arc::new(NavigationCache::default()),
+        }
+    }
+
+    pub fn navigation_cache(&self) -> Arc<NavigationCache> {
+        self.navigation_cache.clone()
     }
 
     pub fn create_dir(&self) -> Result<()> {
@@ -70,9 +82,46 @@ impl Site {
         Ok(())
     }
 
-    pub fn build(&self) -> Result<()> {
+    /// Builds the site, returning any warnings the user should see
+    pub fn build(&self) -> Result<Vec<String>> {
         let generator = SiteGenerator::new(&self.config, &self);
 
         generator.run()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::navigation::Navigation;
+
+    #[test]
+    fn rebuilds_reuse_the_navigation() {
+        let root = std::env::temp_dir().join(format!("doctave-site-{}", std::process::id()));
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs").join("README.md"), "# Hi").unwrap();
+        fs::write(root.join("docs").join("one.md"), "# One").unwrap();
+
+        let config = Config::from_yaml_str(&root, "---\ntitle: Cached\n").unwrap();
+        let site = Site::new(config);
+
+        site.build().unwrap();
+        assert_eq!(site.navigation_cache().hits(), 0);
+
+        // Only the content changed, so the navigation stays the same
+        fs::write(root.join("docs").join("one.md"), "# One\n\nMore content").unwrap();
+        site.build().unwrap();
+        assert_eq!(site.navigation_cache().hits(), 1);
+
+        let before = site.navigation_cache().links().unwrap();
+        fs::write(root.join("docs").join("two.md"), "# Two").unwrap();
+        site.build().unwrap();
+        assert_eq!(site.navigation_cache().hits(), 1);
+
+        let after = site.navigation_cache().links().unwrap();
+        let diff = Navigation::new(&site.config).diff(&before, &after);
+        assert_eq!(diff.added.keys().collect::<Vec<_>>(), vec!["/two"]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
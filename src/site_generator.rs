@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::fs::{self, File};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use elasticlunr::Index;
@@ -35,10 +35,68 @@ impl<'a> SiteGenerator<'a> {
         SiteGenerator { config, site, timestamp: format!("{}", since_the_epoch.as_secs()) }
     }
 
-    pub fn run(&self) -> Result<()> {
-        let root = self.find_docs(self.config.project_root());
-        let nav_builder = Navigation::new(&self.config);
-        let navigation = nav_builder.build_for(&root);
+    /// Builds the site, returning any warnings found along the way
+    pub fn run(&self) -> Result<Vec<String>> {
+        let docs = self.find_docs(self.config.docs_dir());
+        let extra = self.find_extra_docs()?;
+
+        let nav_builder = Navigation::new(&self.config).with_cache(self.site.navigation_cache());
+        let nav_builder = match self.config.language() {
+            Some(language) => nav_builder.with_language(language),
+            None => nav_builder,
+        };
+
+        // The pages of a language directory, like docs/de, are served as if
+        // it was the docs directory. The navigation reads them from their
+        // place in the docs directory, where the navigation rules point.
+        let nav_root = nav_builder.language_dir(&docs)?;
+        let language_docs = self
+            .config
+            .language()
+            .map(|language| self.find_docs(&self.config.docs_dir().join(language)));
+        let root = language_docs.as_ref().unwrap_or(&docs);
+
+        let roots = std::iter::once(root).chain(&extra).collect::<Vec<_>>();
+        for dir in &roots {
+            self.validate_docs(dir)?;
+        }
+
+        // The pages of every directory are built side by side, whether or
+        // not the navigation links to them
+        let nav_roots = std::iter::once(nav_root).chain(&extra).collect::<Vec<_>>();
+        nav_builder.validate_many(&nav_roots)?;
+
+        let merged;
+        let (nav_dir, mut navigation) =
+            if extra.is_empty() || self.config.sidebar_navigation().is_some() {
+                (nav_root, nav_builder.build_for_language(&docs)?)
+            } else {
+                // The pages of the other directories are merged into the
+                // generated navigation
+                merged = nav_builder.merge_dirs(&nav_roots)?;
+                (&merged, nav_builder.build_for(&merged)?)
+            };
+        nav_builder.annotate(&mut navigation);
+        let menus = self.build_menus(&nav_builder, nav_root, &navigation)?;
+        let mut warnings = nav_builder.take_warnings();
+
+        if self.config.sidebar_navigation().is_some() {
+            // A page only has to be in one of the named menus
+            let mut referenced = navigation.clone();
+            for menu in &menus {
+                referenced.extend(menu.links.iter().cloned());
+            }
+
+            for dir in &nav_roots {
+                for path in nav_builder.unreferenced(dir, &referenced) {
+                    warnings.push(format!(
+                        "{} is not included in the navigation rules. \
+                         It will be built, but not linked from the navigation.",
+                        path.display()
+                    ));
+                }
+            }
+        }
 
         self.site.reset()?;
 
@@ -46,10 +104,65 @@ impl<'a> SiteGenerator<'a> {
 
         self.build_includes()?;
         self.build_assets()?;
-        self.build_directory(&root, &navigation, head_include.as_deref())?;
-        self.build_search_index(&root)?;
+        for dir in &roots {
+            self.build_root(
+                dir,
+                &nav_builder,
+                &navigation,
+                &menus,
+                head_include.as_deref(),
+            )?;
+        }
+        warnings.append(&mut nav_builder.take_warnings());
+        self.build_search_index(&roots)?;
 
-        Ok(())
+        if self.config.nav_json_enabled() {
+            self.build_nav_json(&nav_builder, nav_dir)?;
+        }
+
+        if self.config.summary_enabled() {
+            self.build_summary(&nav_builder, &navigation)?;
+        }
+
+        if self.config.single_page_enabled() {
+            self.build_single_page(&nav_builder, nav_root, root, head_include.as_deref())?;
+        }
+
+        Ok(warnings)
+    }
+
+    /// Builds the named navigation menus, if the navigation was split into
+    /// several. The default menu is the navigation already built for the
+    /// sidebar.
+    fn build_menus(
+        &self,
+        nav_builder: &Navigation,
+        root: &Directory,
+        navigation: &[Link],
+    ) -> Result<Vec<Menu>> {
+        self.config
+            .navigation_names()
+            .into_iter()
+            .map(|name| {
+                let links = if Some(name) == self.config.default_navigation_name() {
+                    navigation.to_vec()
+                } else {
+                    let mut links = nav_builder.build_named(name, root)?;
+                    nav_builder.annotate(&mut links);
+                    links
+                };
+
+                Ok(Menu {
+                    name: name.to_owned(),
+                    path: nav_builder
+                        .all_paths(&links)
+                        .into_iter()
+                        .next()
+                        .unwrap_or_default(),
+                    links,
+                })
+            })
+            .collect()
     }
 
     fn read_head_include(&self) -> Result<Option<String>> {
@@ -180,10 +293,32 @@ impl<'a> SiteGenerator<'a> {
             .map_err(|e| Error::handlebars(e, "Could not write custom style sheet"))
     }
 
+    /// Builds the pages of one of the directories the site is made from.
+    /// The directories in `extra_docs_dirs` may only have pages in nested
+    /// directories.
+    fn build_root(
+        &self,
+        root: &Directory,
+        navigation: &Navigation,
+        default_nav: &[Link],
+        menus: &[Menu],
+        head_include: Option<&str>,
+    ) -> Result<()> {
+        if root.docs.is_empty() {
+            root.dirs.iter().try_for_each(|d| {
+                self.build_directory(d, navigation, default_nav, menus, head_include)
+            })
+        } else {
+            self.build_directory(root, navigation, default_nav, menus, head_include)
+        }
+    }
+
     fn build_directory(
         &self,
         dir: &Directory,
-        nav: &[Link],
+        navigation: &Navigation,
+        default_nav: &[Link],
+        menus: &[Menu],
         head_include: Option<&str>,
     ) -> Result<()> {
         fs::create_dir_all(dir.destination(self.config.out_dir()))
@@ -204,14 +339,69 @@ impl<'a> SiteGenerator<'a> {
                         )
                     })?;
 
-                let page_title = if doc.uri_path() == "/" {
+                let page_title = if doc.uri_path(self.config) == Link::root_uri(self.config) {
                     self.config.title().to_string()
                 } else {
                     doc.title().to_string()
                 };
 
+                // Pages in one of the named menus show that menu in the
+                // sidebar, and other pages the default one
+                let menu = menus.iter().position(|m| {
+                    navigation
+                        .all_paths(&m.links)
+                        .contains(&doc.uri_path(self.config))
+                });
+                let nav = menu.map(|i| &menus[i].links[..]).unwrap_or(default_nav);
+                let active_menu = menu.or_else(|| {
+                    let default = self.config.default_navigation_name();
+                    menus.iter().position(|m| Some(m.name.as_str()) == default)
+                });
+
+                let (previous, next) = navigation.neighbors(nav, &doc.uri_path(self.config));
+                let page_number = navigation
+                    .all_paths(nav)
+                    .iter()
+                    .position(|path| *path == doc.uri_path(self.config))
+                    .map(|i| i + 1);
+                let breadcrumbs = navigation.breadcrumbs(nav, &doc.uri_path(self.config));
+                let related = navigation.related(nav, &doc.related());
+                let section = navigation
+                    .parent_of(nav, &doc.uri_path(self.config))
+                    .map(Link::without_children);
+                // The index page of a section lists the pages in it
+                let section_pages = navigation
+                    .subtree(nav, &doc.uri_path(self.config))
+                    .unwrap_or_default()
+                    .iter()
+                    .filter(|link| !link.divider && !link.is_group())
+                    .map(Link::without_children)
+                    .collect();
+                // Only pages in a section list the rest of it. Listing the
+                // whole top level would repeat the sidebar.
+                let siblings = if section.is_some() {
+                    navigation
+                        .siblings(nav, &doc.uri_path(self.config))
+                        .into_iter()
+                        .filter(|link| !link.is_group())
+                        .map(Link::without_children)
+                        .collect()
+                } else {
+                    vec![]
+                };
+                let description = navigation
+                    .find(nav, &doc.uri_path(self.config))
+                    .and_then(|link| link.description.clone());
+
+                let mut page_nav = nav.to_vec();
+                navigation.mark_active(
+                    &mut page_nav,
+                    &doc.uri_path(self.config),
+                    self.config.nav_expand_depth(),
+                );
+
                 let data = TemplateData {
-                    content: doc.html().to_string(),
+                    content: self.resolve_links(doc),
                     headings: doc.headings().iter().map(|heading| {
                         let mut map = BTreeMap::new();
                         map.insert("title", heading.title.clone());
@@ -220,10 +410,31 @@ impl<'a> SiteGenerator<'a> {
 
                         map
                     }).collect::<Vec<_>>(),
-                    navigation: &nav,
-                    current_path: doc.uri_path(),
+                    navigation: page_nav,
+                    menus: menus
+                        .iter()
+                        .enumerate()
+                        .map(|(i, m)| Link {
+                            title: m.name.clone(),
+                            path: m.path.clone(),
+                            active: Some(i) == active_menu,
+                            ..Link::default()
+                        })
+                        .collect(),
+                    previous,
+                    next,
+                    page_number,
+                    page_count: navigation.page_count(nav),
+                    breadcrumbs,
+                    section,
+                    siblings,
+                    section_pages,
+                    related,
+                    current_path: doc.uri_path(self.config),
                     project_title: self.config.title().to_string(),
+                    description,
                     logo: self.config.logo().map(|l| l.to_string()),
+                    base_path: self.config.base_path(),
                     build_mode: self.config.build_mode().to_string(),
                     timestamp: &self.timestamp,
                     page_title,
@@ -241,14 +452,129 @@ impl<'a> SiteGenerator<'a> {
 
         dir.dirs
             .par_iter()
-            .map(|d| self.build_directory(&d, &nav, head_include))
+            .map(|d| self.build_directory(&d, navigation, default_nav, menus, head_include))
             .collect()
     }
 
-    fn build_search_index(&self, root: &Directory) -> Result<()> {
+    /// The HTML of the page, with relative links to other Markdown pages,
+    /// like `../three.md`, pointing at the URI the page is served from
+    fn resolve_links(&self, doc: &Document) -> String {
+        let html = doc.html();
+        let mut output = String::with_capacity(html.len());
+        let mut rest = html;
+
+        while let Some(start) = rest.find("href=\"") {
+            let (before, after) = rest.split_at(start + "href=\"".len());
+            let end = after.find('"').unwrap_or(after.len());
+            let target = &after[..end];
+
+            output.push_str(before);
+            if is_relative_page_link(target) {
+                output.push_str(&Link::resolve_relative(&doc.path, target, self.config));
+            } else {
+                output.push_str(target);
+            }
+
+            rest = &after[end..];
+        }
+
+        output.push_str(rest);
+        output
+    }
+
+    /// Writes the content of every page into print.html, starting with the
+    /// landing page and then in the order the pages appear in the
+    /// navigation, for reading or printing the docs as a whole. The order is
+    /// read from `nav_root`, and the pages from `root`.
+    fn build_single_page(
+        &self,
+        nav_builder: &Navigation,
+        nav_root: &Directory,
+        root: &Directory,
+        head_include: Option<&str>,
+    ) -> Result<()> {
+        fn docs_by_source<'d>(
+            dir: &'d Directory,
+            prefix: &Path,
+            found: &mut BTreeMap<PathBuf, &'d Document>,
+        ) {
+            for doc in &dir.docs {
+                found.insert(prefix.join(&doc.path), doc);
+            }
+            for child in &dir.dirs {
+                docs_by_source(child, prefix, found);
+            }
+        }
+
+        // The navigation names the pages of a language by their place in
+        // the docs directory
+        let prefix = match self.config.language() {
+            Some(language) => Path::new(self.config.docs_dir_name()).join(language),
+            None => PathBuf::from(self.config.docs_dir_name()),
+        };
+
+        let mut docs = BTreeMap::new();
+        docs_by_source(root, &prefix, &mut docs);
+
+        let mut order = nav_builder.reading_order(nav_root)?;
+        if let Some(index) = root.index() {
+            let path = prefix.join(&index.path);
+            if !order.contains(&path) {
+                order.insert(0, path);
+            }
+        }
+
+        let content = order
+            .iter()
+            .filter_map(|path| docs.get(path))
+            .map(|doc| {
+                format!(
+                    "<section class='single-page'>\n{}\n</section>",
+                    self.resolve_links(doc)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let data = TemplateData {
+            content,
+            headings: vec![],
+            navigation: vec![],
+            menus: vec![],
+            previous: None,
+            next: None,
+            page_number: None,
+            page_count: 0,
+            breadcrumbs: vec![],
+            section: None,
+            siblings: vec![],
+            section_pages: vec![],
+            related: vec![],
+            current_path: format!("{}/print", self.config.base_path()),
+            project_title: self.config.title().to_string(),
+            description: None,
+            logo: self.config.logo().map(|l| l.to_string()),
+            base_path: self.config.base_path(),
+            build_mode: self.config.build_mode().to_string(),
+            timestamp: &self.timestamp,
+            page_title: self.config.title().to_string(),
+            head_include,
+        };
+
+        let mut file = File::create(self.config.out_dir().join("print.html"))
+            .map_err(|e| Error::io(e, "Could not create print.html"))?;
+
+        crate::HANDLEBARS
+            .render_to_write("page", &data, &mut file)
+            .map_err(|e| Error::handlebars(e, "Could not render template"))
+    }
+
+    fn build_search_index(&self, roots: &[&Directory]) -> Result<()> {
         let mut index = Index::new(&["title", "uri", "body"]);
 
-        self.build_search_index_for_dir(root, &mut index);
+        for root in roots {
+            self.build_search_index_for_dir(root, &mut index);
+        }
 
         fs::write(
             self.config.out_dir().join("search_index.json"),
@@ -257,13 +583,33 @@ impl<'a> SiteGenerator<'a> {
         .map_err(|e| Error::io(e, "Could not create search index"))
     }
 
+    /// Writes the navigation tree to nav.json, for front-ends that want to
+    /// render the navigation themselves.
+    fn build_nav_json(&self, nav_builder: &Navigation, dir: &Directory) -> Result<()> {
+        let json = nav_builder
+            .to_json(dir)
+            .map_err(|e| Error::new(format!("Could not serialize navigation: {}", e)))?;
+
+        fs::write(self.config.out_dir().join("nav.json"), json.as_bytes())
+            .map_err(|e| Error::io(e, "Could not create nav.json"))
+    }
+
+    /// Writes the navigation to SUMMARY.md as a nested Markdown list, for
+    /// tools that read their table of contents from one.
+    fn build_summary(&self, nav_builder: &Navigation, navigation: &[Link]) -> Result<()> {
+        let summary = format!("# Summary\n\n{}", nav_builder.to_markdown(navigation));
+
+        fs::write(self.config.out_dir().join("SUMMARY.md"), summary.as_bytes())
+            .map_err(|e| Error::io(e, "Could not create SUMMARY.md"))
+    }
+
     fn build_search_index_for_dir(&self, root: &Directory, index: &mut Index) {
         for doc in &root.docs {
             index.add_doc(
                 &doc.id.to_string(),
                 &[
                     &doc.title(),
-                    &doc.uri_path().as_str(),
+                    &doc.uri_path(self.config).as_str(),
                     doc.markdown_section(),
                 ],
             );
@@ -273,21 +619,71 @@ impl<'a> SiteGenerator<'a> {
         }
     }
 
-    fn find_docs(&self, project_root: &Path) -> Directory {
-        let mut root_dir = self
-            .walk_dir(project_root.join("docs"))
-            .unwrap_or(Directory {
-                path: project_root.join("docs"),
-                docs: vec![],
-                dirs: vec![],
-            });
+    fn validate_docs(&self, dir: &Directory) -> Result<()> {
+        for doc in &dir.docs {
+            doc.validate()?;
+        }
+        for child in &dir.dirs {
+            self.validate_docs(child)?;
+        }
+
+        Ok(())
+    }
+
+    fn find_docs(&self, docs_dir: &Path) -> Directory {
+        let mut root_dir = self.walk_dir(docs_dir, docs_dir).unwrap_or(Directory {
+            path: docs_dir.to_path_buf(),
+            docs: vec![],
+            dirs: vec![],
+        });
 
-        self.generate_missing_indices(&mut root_dir);
+        self.generate_missing_indices(&mut root_dir, docs_dir);
 
         root_dir
     }
 
-    fn walk_dir<P: AsRef<Path>>(&self, dir: P) -> Option<Directory> {
+    /// Finds the pages in the directories listed in `extra_docs_dirs`. Their
+    /// pages are served as if they were in the docs directory, so they can't
+    /// have an index page of their own, which would replace the landing
+    /// page of the site.
+    fn find_extra_docs(&self) -> Result<Vec<Directory>> {
+        let mut found = vec![];
+
+        for path in self.config.extra_docs_dirs() {
+            let mut dir = self.read_dir(path, path);
+
+            if let Some(index) = dir.index() {
+                return Err(Error::new(format!(
+                    "Found an index page at {}, which would replace the landing page of the site. \
+                     Pages in extra_docs_dirs have to be in a directory of their own.",
+                    path.join(&index.path).display()
+                )));
+            }
+
+            for child in &mut dir.dirs {
+                self.generate_missing_indices(child, path);
+            }
+
+            found.push(dir);
+        }
+
+        Ok(found)
+    }
+
+    /// Like `read_dir`, but leaves out directories without any pages
+    fn walk_dir<P: AsRef<Path>>(&self, dir: P, root: &Path) -> Option<Directory> {
+        let dir = self.read_dir(dir, root);
+
+        if dir.docs.is_empty() {
+            None
+        } else {
+            Some(dir)
+        }
+    }
+
+    /// Reads the pages in the directory and the ones nested under it. The
+    /// paths of the pages are relative to `root`.
+    fn read_dir<P: AsRef<Path>>(&self, dir: P, root: &Path) -> Directory {
         let mut docs = vec![];
         let mut dirs = vec![];
 
@@ -299,9 +695,15 @@ impl<'a> SiteGenerator<'a> {
             .filter_map(|e| e.ok())
         {
             if entry.file_type().is_file() && entry.path().extension() == Some(OsStr::new("md")) {
-                let path = entry.path().strip_prefix(self.config.docs_dir()).unwrap();
+                let path = entry.path().strip_prefix(root).unwrap();
+
+                let mut doc = Document::load(entry.path(), path);
+                doc.set_index(self.config.is_index_file(path));
+                if self.config.slugify_titles() {
+                    doc.slugify_title();
+                }
 
-                docs.push(Document::load(entry.path(), path));
+                docs.push(doc);
             } else {
                 let path = entry.into_path();
 
@@ -309,44 +711,47 @@ impl<'a> SiteGenerator<'a> {
                     continue;
                 }
 
-                if let Some(dir) = self.walk_dir(path) {
+                if let Some(dir) = self.walk_dir(path, root) {
                     dirs.push(dir);
                 }
             }
         }
 
-        if docs.is_empty() {
-            None
-        } else {
-            Some(Directory {
-                path: current_dir.to_path_buf(),
-                docs,
-                dirs,
-            })
+        // Pages sorted by date fall back to their git history, which is
+        // only worth asking for when the directory needs it.
+        let sort_by_date = docs
+            .iter()
+            .any(|d| d.is_index() && d.sort() == Some("date"));
+
+        if sort_by_date {
+            for doc in &mut docs {
+                doc.load_git_date(&root.join(&doc.path));
+            }
+        }
+
+        Directory {
+            path: current_dir.to_path_buf(),
+            docs,
+            dirs,
         }
     }
 
-    fn generate_missing_indices(&self, dir: &mut Directory) {
-        if dir
-            .docs
-            .iter()
-            .find(|d| d.original_file_name() == Some(OsStr::new("README.md")))
-            .is_none()
-        {
-            let new_index = self.generate_missing_index(dir);
+    fn generate_missing_indices(&self, dir: &mut Directory, root: &Path) {
+        if dir.index().is_none() {
+            let new_index = self.generate_missing_index(dir, root);
             dir.docs.push(new_index);
         }
 
         for mut child in &mut dir.dirs {
-            self.generate_missing_indices(&mut child);
+            self.generate_missing_indices(&mut child, root);
         }
     }
 
-    fn generate_missing_index(&self, dir: &mut Directory) -> Document {
+    fn generate_missing_index(&self, dir: &mut Directory, root: &Path) -> Document {
         let content = dir
             .docs
             .iter()
-            .map(|d| format!("* [{}]({})", d.title(), d.uri_path()))
+            .map(|d| format!("* [{}]({})", d.title(), d.uri_path(self.config)))
             .collect::<Vec<_>>()
             .join("\n");
 
@@ -357,9 +762,9 @@ impl<'a> SiteGenerator<'a> {
         );
 
         let tmp = dir.path().join("README.md");
-        let path = tmp.strip_prefix(self.config.docs_dir()).unwrap();
+        let path = tmp.strip_prefix(root).unwrap();
 
-        Document::new(
+        Document::generated_index(
             path,
             format!(
                 "# Index of {}\n \
@@ -383,15 +788,49 @@ impl<'a> SiteGenerator<'a> {
     }
 }
 
+/// Whether the link points to another Markdown page relative to the current
+/// one. Links to other sites and absolute paths are left alone.
+fn is_relative_page_link(target: &str) -> bool {
+    let path = target.split('#').next().unwrap_or_default();
+
+    !target.contains(':') && !target.starts_with('/') && path.ends_with(".md")
+}
+
+/// One of the named navigation menus, shown as tabs above the sidebar
+struct Menu {
+    name: String,
+    /// The first page in the menu, which its tab links to
+    path: String,
+    links: Vec<Link>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct TemplateData<'a> {
     pub content: String,
     pub headings: Vec<BTreeMap<&'static str, String>>,
-    pub navigation: &'a [Link],
+    pub navigation: Vec<Link>,
+    pub menus: Vec<Link>,
+    pub previous: Option<Link>,
+    pub next: Option<Link>,
+    /// Where the page is in the reading order of the navigation, starting
+    /// from 1, if it is in the navigation
+    pub page_number: Option<usize>,
+    pub page_count: usize,
+    pub breadcrumbs: Vec<Link>,
+    /// The link the page is nested under in the navigation, if any
+    pub section: Option<Link>,
+    /// The other pages in the same section as the page
+    pub siblings: Vec<Link>,
+    /// The pages nested under the page, if it is the index of a section
+    pub section_pages: Vec<Link>,
+    pub related: Vec<Link>,
     pub head_include: Option<&'a str>,
     pub current_path: String,
     pub page_title: String,
+    /// The description of the page in the navigation, for its meta tags
+    pub description: Option<String>,
     pub logo: Option<String>,
+    pub base_path: &'a str,
     pub project_title: String,
     pub build_mode: String,
     pub timestamp: &'a str,
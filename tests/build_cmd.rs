@@ -133,6 +133,48 @@ integration_test!(build_navigation_nested, |area| {
     area.assert_exists(Path::new("site").join("nested").join("howto_build.html"));
 });
 
+integration_test!(build_page_links, |area| {
+    area.create_config();
+    area.mkdir("docs");
+    area.write_file(Path::new("docs").join("README.md"), b"# Some content");
+    area.write_file(
+        Path::new("docs").join("one.md"),
+        indoc! {"
+        ---
+        title: One
+        ---
+
+        # One
+    "}
+        .as_bytes(),
+    );
+    area.write_file(
+        Path::new("docs").join("two.md"),
+        indoc! {"
+        ---
+        title: Two
+        ---
+
+        # Two
+    "}
+        .as_bytes(),
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    let one = Path::new("site").join("one.html");
+    area.assert_contains(&one, "<a class='page-link-next' href=\"/two\">Two →</a>");
+    area.refute_contains(&one, "page-link-previous");
+
+    let two = Path::new("site").join("two.html");
+    area.assert_contains(
+        &two,
+        "<a class='page-link-previous' href=\"/one\">← One</a>",
+    );
+    area.refute_contains(&two, "page-link-next");
+});
+
 integration_test!(mermaid_js, |area| {
     area.mkdir("docs");
     area.create_config();
@@ -265,6 +307,33 @@ integration_test!(missing_directory_index, |area| {
     );
 });
 
+integration_test!(missing_directory_index_navigation, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs").join("getting-started"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Some content");
+    area.write_file(
+        Path::new("docs").join("getting-started").join("install.md"),
+        b"# Install",
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+    assert_output(
+        &result,
+        "Warning: The directory docs/getting-started does not have a README.md. \
+         Using \"Getting Started\" as its title in the navigation.",
+    );
+
+    // The generated index is still built, but the navigation links to the
+    // first page in the directory
+    area.assert_exists(Path::new("site").join("getting-started").join("index.html"));
+    let index = Path::new("site").join("index.html");
+    area.assert_contains(
+        &index,
+        "<a href=\"/getting-started/install\">Getting Started</a>",
+    );
+});
+
 integration_test!(code_syntax_highlight, |area| {
     area.create_config();
     area.mkdir(Path::new("docs"));
@@ -374,6 +443,531 @@ integration_test!(custom_colors_invalid, |area| {
     assert_output(&result, "Found 'not-a-color'");
 });
 
+integration_test!(navigation_duplicate_warning, |area| {
+    area.mkdir(Path::new("docs"));
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Duplicates
+    navigation:
+      - path: docs/one.md
+      - path: docs/one.md
+    "}
+        .as_bytes(),
+    );
+
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("one.md"), b"# One");
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+    assert_output(
+        &result,
+        "Warning: Found docs/one.md more than once in the navigation rules.",
+    );
+});
+
+integration_test!(navigation_unreferenced_warning, |area| {
+    area.mkdir(Path::new("docs"));
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Forgotten
+    navigation:
+      - path: docs/one.md
+    "}
+        .as_bytes(),
+    );
+
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("one.md"), b"# One");
+    area.write_file(Path::new("docs").join("two.md"), b"# Two");
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+    assert_output(
+        &result,
+        "Warning: docs/two.md is not included in the navigation rules.",
+    );
+    refute_output(&result, "docs/one.md");
+    refute_output(&result, "docs/README.md");
+});
+
+integration_test!(named_navigation_menus, |area| {
+    area.mkdir(Path::new("docs"));
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Menus
+    navigation:
+      guides:
+        - path: docs/tutorial.md
+      api:
+        - path: docs/reference.md
+    "}
+        .as_bytes(),
+    );
+
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("tutorial.md"), b"# Tutorial");
+    area.write_file(Path::new("docs").join("reference.md"), b"# Reference");
+    area.write_file(Path::new("docs").join("forgotten.md"), b"# Forgotten");
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+    assert_output(
+        &result,
+        "Warning: docs/forgotten.md is not included in the navigation rules.",
+    );
+    refute_output(&result, "docs/tutorial.md");
+    refute_output(&result, "docs/reference.md");
+
+    // Pages outside of the menus get the first menu in doctave.yaml, and
+    // the tabs are shown in the same order
+    let index = Path::new("site").join("index.html");
+    area.assert_contains(&index, "<a class=\"active\" href=\"/tutorial\">guides</a>");
+    area.assert_contains(&index, "<a href=\"/reference\">api</a>");
+    let html = std::fs::read_to_string(area.path.join(&index)).unwrap();
+    assert!(html.find(">guides</a>").unwrap() < html.find(">api</a>").unwrap());
+    area.assert_contains(&index, "<a href=\"/tutorial\">Tutorial</a>");
+    area.refute_contains(&index, "<a href=\"/reference\">Reference</a>");
+
+    let reference = Path::new("site").join("reference.html");
+    area.assert_contains(
+        &reference,
+        "<a class=\"active\" href=\"/reference\">api</a>",
+    );
+    area.assert_contains(
+        &reference,
+        "<a class=\"active\" href=\"/reference\">Reference</a>",
+    );
+    area.refute_contains(&reference, "<a href=\"/tutorial\">Tutorial</a>");
+});
+
+integration_test!(navigation_collapsed, |area| {
+    area.mkdir(Path::new("docs").join("runbooks"));
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Collapsed
+    navigation:
+      - path: docs/runbooks
+        children: \"*\"
+        collapsed: true
+    "}
+        .as_bytes(),
+    );
+
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(
+        Path::new("docs").join("runbooks").join("README.md"),
+        b"# Runbooks",
+    );
+    area.write_file(
+        Path::new("docs").join("runbooks").join("deploy.md"),
+        b"# Deploy",
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    let index = Path::new("site").join("index.html");
+    area.assert_contains(&index, "<button class='nav-toggle' aria-expanded='false'");
+    area.assert_contains(&index, "<ul hidden>");
+
+    let deploy = Path::new("site").join("runbooks").join("deploy.html");
+    area.assert_contains(&deploy, "<button class='nav-toggle' aria-expanded='true'");
+    area.refute_contains(&deploy, "<ul hidden>");
+});
+
+integration_test!(navigation_link_classes, |area| {
+    area.mkdir(Path::new("docs"));
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Classes
+    navigation:
+      - path: docs/one.md
+        class: highlight
+      - path: docs/two.md
+    "}
+        .as_bytes(),
+    );
+
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("one.md"), b"# One");
+    area.write_file(Path::new("docs").join("two.md"), b"# Two");
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    let index = Path::new("site").join("index.html");
+    area.assert_contains(&index, "<a class=\"highlight\" href=\"/one\">One</a>");
+    area.assert_contains(&index, "<a href=\"/two\">Two</a>");
+
+    let one = Path::new("site").join("one.html");
+    area.assert_contains(&one, "<a class=\"active highlight\" href=\"/one\">One</a>");
+});
+
+integration_test!(page_description, |area| {
+    area.mkdir(Path::new("docs"));
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Descriptions
+    navigation:
+      - path: docs/one.md
+      - path: docs/two.md
+        description: The second page
+    "}
+        .as_bytes(),
+    );
+
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(
+        Path::new("docs").join("one.md"),
+        indoc! {"
+        ---
+        title: One
+        description: The first page
+        ---
+
+        # One
+    "}
+        .as_bytes(),
+    );
+    area.write_file(Path::new("docs").join("two.md"), b"# Two");
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    area.assert_contains(
+        Path::new("site").join("one.html"),
+        "<meta name=\"description\" content=\"The first page\">",
+    );
+    area.assert_contains(
+        Path::new("site").join("two.html"),
+        "<meta name=\"description\" content=\"The second page\">",
+    );
+    area.assert_contains(
+        Path::new("site").join("index.html"),
+        "<meta name=\"description\" content=\"Documentation for Descriptions\">",
+    );
+});
+
+integration_test!(navigation_ids, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs").join("guides"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(
+        Path::new("docs").join("guides").join("README.md"),
+        b"# Guides",
+    );
+    area.write_file(
+        Path::new("docs").join("guides").join("first-steps.md"),
+        b"# First steps",
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    let index = Path::new("site").join("index.html");
+    area.assert_contains(
+        &index,
+        "<li id='nav--guides'><a href=\"/guides\">Guides</a>",
+    );
+    area.assert_contains(
+        &index,
+        "<li id='nav--guides--first-steps'><a href=\"/guides/first-steps\">First steps</a></li>",
+    );
+});
+
+integration_test!(navigation_ids_unique, |area| {
+    area.mkdir(Path::new("docs").join("guides"));
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Ids
+    index_as_child: true
+    "}
+        .as_bytes(),
+    );
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(
+        Path::new("docs").join("guides").join("README.md"),
+        b"# Guides",
+    );
+    area.write_file(
+        Path::new("docs").join("guides").join("install.md"),
+        b"# Install",
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    // The overview links to the same page as its directory
+    let index = Path::new("site").join("index.html");
+    area.assert_contains(
+        &index,
+        "<li id='nav--guides'><a href=\"/guides\">Guides</a>",
+    );
+    area.assert_contains(
+        &index,
+        "<li id='nav--guides---2'><a href=\"/guides\">Overview</a></li>",
+    );
+});
+
+integration_test!(page_section, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs").join("guides"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("one.md"), b"# One");
+    area.write_file(
+        Path::new("docs").join("guides").join("README.md"),
+        b"# Guides",
+    );
+    area.write_file(
+        Path::new("docs").join("guides").join("install.md"),
+        b"# Install",
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    area.assert_contains(
+        Path::new("site").join("guides").join("install.html"),
+        "<p class='page-section'>Part of <a href=\"/guides\">Guides</a></p>",
+    );
+    area.refute_contains(Path::new("site").join("one.html"), "class='page-section'");
+});
+
+integration_test!(extra_docs_dirs, |area| {
+    area.mkdir(Path::new("docs"));
+    area.mkdir(Path::new("api").join("reference"));
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Extra
+    extra_docs_dirs:
+      - api
+    "}
+        .as_bytes(),
+    );
+
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("guide.md"), b"# Guide");
+    area.write_file(
+        Path::new("api").join("reference").join("client.md"),
+        b"# Client",
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    area.assert_exists(Path::new("site").join("reference").join("client.html"));
+
+    let index = Path::new("site").join("index.html");
+    area.assert_contains(&index, "<title>Extra</title>");
+    area.assert_contains(&index, "href=\"/guide\"");
+    area.assert_contains(&index, "href=\"/reference/client\"");
+});
+
+integration_test!(extra_docs_dirs_index, |area| {
+    area.mkdir(Path::new("docs"));
+    area.mkdir(Path::new("api"));
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Extra
+    extra_docs_dirs:
+      - api
+    "}
+        .as_bytes(),
+    );
+
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("api").join("README.md"), b"# API");
+
+    let result = area.cmd(&["build"]);
+    assert_failed(&result);
+    assert_output(&result, "would replace the landing page of the site");
+});
+
+integration_test!(extra_docs_dirs_with_navigation, |area| {
+    area.mkdir(Path::new("docs"));
+    area.mkdir(Path::new("api").join("reference"));
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Extra
+    extra_docs_dirs:
+      - api
+    navigation:
+      - path: docs/guide.md
+    "}
+        .as_bytes(),
+    );
+
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("guide.md"), b"# Guide");
+    area.write_file(
+        Path::new("api").join("reference").join("client.md"),
+        b"# Client",
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+    assert_output(
+        &result,
+        "Warning: api/reference/client.md is not included in the navigation rules.",
+    );
+
+    area.write_file(Path::new("api").join("guide.md"), b"# Other guide");
+
+    let result = area.cmd(&["build"]);
+    assert_failed(&result);
+    assert_output(&result, "Found more than one page for /guide");
+});
+
+integration_test!(page_progress, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("one.md"), b"# One");
+    area.write_file(Path::new("docs").join("two.md"), b"# Two");
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    area.assert_contains(
+        Path::new("site").join("two.html"),
+        "<p class='page-progress'>Page 2 of 2</p>",
+    );
+    area.refute_contains(Path::new("site").join("index.html"), "page-progress");
+});
+
+integration_test!(relative_links, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs").join("child"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(
+        Path::new("docs").join("child").join("README.md"),
+        b"# Child",
+    );
+    area.write_file(Path::new("docs").join("child").join("three.md"), b"# Three");
+    area.write_file(
+        Path::new("docs").join("child").join("two.md"),
+        indoc! {"
+        # Two
+
+        See [three](./three.md#usage), go [back](../README.md) or read the
+        [spec](https://example.com/spec.md).
+    "}
+        .as_bytes(),
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    let two = Path::new("site").join("child").join("two.html");
+    area.assert_contains(&two, "href=\"/child/three#usage\"");
+    area.assert_contains(&two, "href=\"/\"");
+    area.assert_contains(&two, "href=\"https://example.com/spec.md\"");
+});
+
+integration_test!(section_pages, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs").join("guides"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("one.md"), b"# One");
+    area.write_file(
+        Path::new("docs").join("guides").join("README.md"),
+        b"# Guides",
+    );
+    area.write_file(
+        Path::new("docs").join("guides").join("install.md"),
+        b"# Install",
+    );
+    area.write_file(
+        Path::new("docs").join("guides").join("upgrade.md"),
+        b"# Upgrade",
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    let install = Path::new("site").join("guides").join("install.html");
+    area.assert_contains(
+        &install,
+        "<p class='related-pages-header'>More in Guides</p>",
+    );
+    area.assert_contains(&install, "<li><a href=\"/guides/upgrade\">Upgrade</a></li>");
+    area.refute_contains(Path::new("site").join("one.html"), "section-pages");
+
+    let guides = Path::new("site").join("guides").join("index.html");
+    area.assert_contains(
+        &guides,
+        "<p class='related-pages-header'>In this section</p>",
+    );
+    area.refute_contains(&install, "In this section");
+});
+
+integration_test!(slug, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(
+        Path::new("docs").join("001-intro.md"),
+        indoc! {"
+        ---
+        title: Intro
+        slug: intro
+        ---
+
+        # Intro
+    "}
+        .as_bytes(),
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    area.assert_exists(Path::new("site").join("intro.html"));
+    area.refute_exists(Path::new("site").join("001-intro.html"));
+    area.assert_contains(Path::new("site").join("index.html"), "href=\"/intro\"");
+});
+
+integration_test!(slug_invalid, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(
+        Path::new("docs").join("intro.md"),
+        indoc! {"
+        ---
+        slug: not a/slug
+        ---
+
+        # Intro
+    "}
+        .as_bytes(),
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_failed(&result);
+    assert_output(&result, "Error: Invalid slug 'not a/slug' in intro.md.");
+});
+
 integration_test!(release_mode, |area| {
     area.create_config();
     area.mkdir(Path::new("docs"));
@@ -389,6 +983,134 @@ integration_test!(release_mode, |area| {
     assert!(!livereload_js.exists());
 });
 
+integration_test!(nav_json, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs").join("child"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("one.md"), b"# One");
+    area.write_file(
+        Path::new("docs").join("child").join("README.md"),
+        b"# Child",
+    );
+    area.write_file(Path::new("docs").join("child").join("two.md"), b"# Two");
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+    area.refute_exists(Path::new("site").join("nav.json"));
+
+    let result = area.cmd(&["build", "--nav-json"]);
+    assert_success(&result);
+
+    let nav = Path::new("site").join("nav.json");
+    area.assert_contains(&nav, "\"path\":\"/one\"");
+    area.assert_contains(&nav, "\"children\":[{\"path\":\"/child/two\"");
+});
+
+integration_test!(summary, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs").join("child"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("one.md"), b"# One");
+    area.write_file(
+        Path::new("docs").join("child").join("README.md"),
+        b"# Child",
+    );
+    area.write_file(Path::new("docs").join("child").join("two.md"), b"# Two");
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+    area.refute_exists(Path::new("site").join("SUMMARY.md"));
+
+    let result = area.cmd(&["build", "--summary"]);
+    assert_success(&result);
+
+    let summary = Path::new("site").join("SUMMARY.md");
+    area.assert_contains(
+        &summary,
+        "# Summary\n\n- [Child](/child)\n  - [Two](/child/two)\n- [One](/one)\n",
+    );
+});
+
+integration_test!(single_page, |area| {
+    area.mkdir(Path::new("docs"));
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Single page
+    navigation:
+      - path: docs/two.md
+      - path: docs/one.md
+    "}
+        .as_bytes(),
+    );
+
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi\n\nWelcome");
+    area.write_file(Path::new("docs").join("one.md"), b"# One\n\nFirst page");
+    area.write_file(Path::new("docs").join("two.md"), b"# Two\n\nSecond page");
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+    area.refute_exists(Path::new("site").join("print.html"));
+
+    let result = area.cmd(&["build", "--single-page"]);
+    assert_success(&result);
+
+    let print = std::fs::read_to_string(area.path.join("site").join("print.html")).unwrap();
+    let position = |needle: &str| print.find(needle).unwrap();
+
+    assert!(position("Welcome") < position("Second page"));
+    assert!(position("Second page") < position("First page"));
+});
+
+integration_test!(language, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs").join("en"));
+    area.mkdir(Path::new("docs").join("de"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Languages");
+    area.write_file(Path::new("docs").join("en").join("README.md"), b"# Start");
+    area.write_file(Path::new("docs").join("en").join("guide.md"), b"# Guide");
+    area.write_file(Path::new("docs").join("de").join("README.md"), b"# Start");
+    area.write_file(
+        Path::new("docs").join("de").join("guide.md"),
+        b"# Anleitung",
+    );
+
+    let result = area.cmd(&["build", "--language", "de"]);
+    assert_success(&result);
+
+    let index = Path::new("site").join("index.html");
+    area.assert_contains(&index, "<a href=\"/guide\">Anleitung</a>");
+    area.assert_contains(Path::new("site").join("guide.html"), "Anleitung");
+    area.refute_exists(Path::new("site").join("de"));
+    area.refute_exists(Path::new("site").join("en"));
+    area.refute_exists(Path::new("site").join("en"));
+
+    let result = area.cmd(&["build", "--language", "fr"]);
+    assert_failed(&result);
+    assert_output(&result, "Could not find a directory for the language fr");
+});
+
+integration_test!(duplicate_uri, |area| {
+    area.create_config();
+    area.mkdir(Path::new("docs").join("one"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(Path::new("docs").join("one.md"), b"# One");
+    area.write_file(
+        Path::new("docs").join("one").join("README.md"),
+        b"# Also one",
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_failed(&result);
+    assert_output(&result, "Conflicting pages in the docs:");
+    assert_output(
+        &result,
+        "Found two pages served from /one: docs/one.md and docs/one/README.md.",
+    );
+    refute_output(&result, "doctave.yaml");
+});
+
 integration_test!(custom_logo, |area| {
     area.mkdir(Path::new("docs").join("_include").join("assets"));
     area.write_file(Path::new("docs").join("README.md"), b"# Hi");
@@ -421,6 +1143,39 @@ integration_test!(custom_logo, |area| {
     area.assert_contains(&index, "/assets/fake-logo.png");
 });
 
+integration_test!(base_path_assets, |area| {
+    area.mkdir(Path::new("docs").join("_include").join("assets"));
+    area.write_file(Path::new("docs").join("README.md"), b"# Hi");
+    area.write_file(
+        Path::new("docs")
+            .join("_include")
+            .join("assets")
+            .join("fake-logo.png"),
+        b"",
+    );
+
+    area.write_file(
+        Path::new("doctave.yaml"),
+        indoc! {"
+    ---
+    title: Base path
+    logo: assets/fake-logo.png
+    base_path: /docs
+    "}
+        .as_bytes(),
+    );
+
+    let result = area.cmd(&["build"]);
+    assert_success(&result);
+
+    let index = Path::new("site").join("index.html");
+
+    area.assert_contains(&index, "href=\"/docs/assets/doctave-style.css?v=");
+    area.assert_contains(&index, "src=\"/docs/assets/doctave-app.js?v=");
+    area.assert_contains(&index, "src=\"/docs/assets/fake-logo.png\"");
+    area.refute_contains(&index, "\"/assets/");
+});
+
 integration_test!(include_header, |area| {
     area.create_config();
     area.mkdir(Path::new("docs").join("_include"));